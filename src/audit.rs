@@ -2,7 +2,9 @@
 
 use crate::config::AuditConfig;
 use crate::error::SafetyCategory;
-use crate::types::{AuditEntry, AuditResult, Direction, GuardContext, SanitizeResult};
+use crate::types::{
+    AuditEntry, AuditResult, Direction, GuardContext, MonitoredAction, SanitizeResult,
+};
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
@@ -28,6 +30,19 @@ impl AuditLogger {
         content: &str,
         result: &SanitizeResult,
         duration_ms: u64,
+    ) {
+        self.log_with_monitored(context, direction, content, result, &[], duration_ms);
+    }
+
+    /// Log a sanitization event along with actions recorded in monitor mode
+    pub fn log_with_monitored(
+        &self,
+        context: &GuardContext,
+        direction: Direction,
+        content: &str,
+        result: &SanitizeResult,
+        monitored: &[MonitoredAction],
+        duration_ms: u64,
     ) {
         if !self.config.enabled {
             return;
@@ -47,6 +62,7 @@ impl AuditLogger {
                 },
             },
             processing_time_ms: duration_ms,
+            monitored: monitored.to_vec(),
         };
 
         self.emit(&entry, content);
@@ -71,6 +87,7 @@ impl AuditLogger {
             content_hash: hash_content(content),
             result: AuditResult::Blocked { category },
             processing_time_ms: 0,
+            monitored: vec![],
         };
 
         #[cfg(feature = "audit")]
@@ -98,12 +115,19 @@ impl AuditLogger {
                 String::new()
             };
 
+            let monitored_info = if entry.monitored.is_empty() {
+                String::new()
+            } else {
+                format!(" | monitored={}", entry.monitored.len())
+            };
+
             println!(
-                "[AUDIT] {} | {} | {:?} | result={:?} | {}ms{}",
+                "[AUDIT] {} | {} | {:?} | result={:?}{} | {}ms{}",
                 entry.context.timestamp.format("%Y-%m-%d %H:%M:%S"),
                 entry.context.request_id,
                 entry.direction,
                 entry.result,
+                monitored_info,
                 entry.processing_time_ms,
                 content_info
             );
@@ -125,6 +149,7 @@ impl AuditLogger {
                 direction = ?entry.direction,
                 content_hash = %entry.content_hash,
                 result = ?entry.result,
+                monitored = entry.monitored.len(),
                 processing_time_ms = entry.processing_time_ms,
                 content = ?content_field,
                 "Guard audit"
@@ -184,6 +209,39 @@ mod tests {
         assert_eq!(truncate("this is a longer string", 10), "this is a ...");
     }
 
+    #[test]
+    fn test_monitored_written_to_log_file() {
+        let path = std::env::temp_dir().join(format!("hanzo-guard-{}.jsonl", uuid::Uuid::new_v4()));
+        let logger = AuditLogger::new(AuditConfig {
+            enabled: true,
+            log_file: Some(path.to_string_lossy().into_owned()),
+            ..Default::default()
+        });
+
+        let monitored = [MonitoredAction {
+            stage: "injection".to_string(),
+            action: crate::stage::StageAction::Block {
+                reason: "test".to_string(),
+                category: SafetyCategory::Jailbreak,
+            },
+        }];
+        logger.log_with_monitored(
+            &GuardContext::default(),
+            Direction::Input,
+            "hello",
+            &SanitizeResult::Clean("hello".to_string()),
+            &monitored,
+            1,
+        );
+
+        let line = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let entry: AuditEntry = serde_json::from_str(line.trim()).unwrap();
+        assert!(matches!(entry.result, AuditResult::Passed));
+        assert_eq!(entry.monitored.len(), 1);
+        assert_eq!(entry.monitored[0].stage, "injection");
+    }
+
     #[test]
     fn test_audit_disabled() {
         let config = AuditConfig {
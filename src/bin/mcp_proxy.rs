@@ -3,12 +3,14 @@
 //! Usage:
 //!   guard-mcp -- npx @hanzo/mcp serve
 //!   guard-mcp -- python -m mcp_server
+//!   guard-mcp --config guard.toml -- npx @hanzo/mcp serve
 //!
 //! Wraps any MCP server and filters tool inputs/outputs through guard.
 
 use hanzo_guard::{Guard, GuardConfig, SanitizeResult};
 use serde_json::Value;
 use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
 
@@ -107,8 +109,9 @@ fn main() {
         println!("    guard-mcp [OPTIONS] -- <COMMAND> [ARGS...]");
         println!();
         println!("OPTIONS:");
-        println!("    -v, --verbose    Show filtered messages");
-        println!("    -h, --help       Print help");
+        println!("    -c, --config <FILE>    Load config from TOML/YAML/JSON file");
+        println!("    -v, --verbose          Show filtered messages");
+        println!("    -h, --help             Print help");
         println!();
         println!("EXAMPLES:");
         println!("    guard-mcp -- npx @hanzo/mcp serve");
@@ -138,11 +141,26 @@ fn main() {
     let command = &args[cmd_start];
     let cmd_args = &args[cmd_start + 1..];
 
+    // Options are only read before the -- separator
+    let config_path = args[..cmd_start]
+        .iter()
+        .position(|a| a == "--config" || a == "-c")
+        .and_then(|pos| args.get(pos + 1))
+        .filter(|path| path.as_str() != "--");
+
     // Create async runtime
     let rt = tokio::runtime::Runtime::new().unwrap();
 
     // Initialize guard
-    let guard = Arc::new(Guard::new(GuardConfig::default()));
+    let guard = match GuardConfig::load(config_path.map(Path::new))
+        .and_then(|config| Guard::builder().with_config(config).try_build())
+    {
+        Ok(guard) => Arc::new(guard),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
 
     // Spawn the wrapped MCP server
     let mut child = Command::new(command)
@@ -3,10 +3,14 @@
 //! Usage:
 //!   guard-proxy --upstream https://api.openai.com --port 8080
 //!   guard-proxy --upstream https://api.anthropic.com --port 8080
+//!   guard-proxy --config guard.toml
 //!
 //! Then point your LLM client to http://localhost:8080 instead of the upstream API.
+//!
+//! With `--config`, the guard policy is reloaded on SIGHUP and whenever the
+//! config file changes. In-flight requests finish with the policy they started with.
 
-use hanzo_guard::{Guard, GuardConfig, SanitizeResult};
+use hanzo_guard::{Guard, GuardConfig, GuardContext, SanitizeResult};
 use http_body_util::{BodyExt, Full};
 use hyper::body::Bytes;
 use hyper::server::conn::http1;
@@ -15,18 +19,98 @@ use hyper::{Request, Response, StatusCode};
 use hyper_util::rt::TokioIo;
 use serde_json::{json, Value};
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
 use tokio::net::TcpListener;
 
+/// How often the config file is checked for changes
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 struct ProxyState {
-    guard: Guard,
+    guard: RwLock<Arc<Guard>>,
+    config_path: Option<PathBuf>,
     upstream: String,
     client: reqwest::Client,
 }
 
+impl ProxyState {
+    /// Snapshot the current guard
+    fn guard(&self) -> Arc<Guard> {
+        self.guard.read().unwrap().clone()
+    }
+
+    /// Rebuild the guard from the config file and swap it in
+    ///
+    /// The previous guard stays active if the new config fails to load or validate.
+    fn reload(&self) {
+        let result = GuardConfig::load(self.config_path.as_deref())
+            .and_then(|config| self.guard().reload(config));
+
+        match result {
+            Ok(guard) => {
+                *self.guard.write().unwrap() = Arc::new(guard);
+                eprintln!("Reloaded guard config");
+            }
+            Err(e) => eprintln!("Config reload failed, keeping previous config: {e}"),
+        }
+    }
+}
+
+/// Reload the guard whenever the config file's modification time changes
+async fn watch_config(state: Arc<ProxyState>, path: PathBuf) {
+    let modified = |path: &PathBuf| -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    };
+
+    let mut last_modified = modified(&path);
+    let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let current = modified(&path);
+        if current.is_some() && current != last_modified {
+            last_modified = current;
+            state.reload();
+        }
+    }
+}
+
+/// Reload the guard on SIGHUP
+#[cfg(unix)]
+async fn reload_on_sighup(state: Arc<ProxyState>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            eprintln!("Failed to install SIGHUP handler: {e}");
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        state.reload();
+    }
+}
+
 async fn handle_request(
     req: Request<hyper::body::Incoming>,
     state: Arc<ProxyState>,
+) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    // One context per request so tokenized PII is restored in its response only
+    let guard = state.guard();
+    let ctx = GuardContext::new();
+    let response = proxy_request(req, &state, &guard, &ctx).await;
+    if let Err(e) = guard.release_context(&ctx).await {
+        eprintln!("Failed to release request tokens: {e}");
+    }
+    response
+}
+
+async fn proxy_request(
+    req: Request<hyper::body::Incoming>,
+    state: &ProxyState,
+    guard: &Guard,
+    ctx: &GuardContext,
 ) -> Result<Response<Full<Bytes>>, hyper::Error> {
     let method = req.method().clone();
     let uri = req.uri().clone();
@@ -38,7 +122,7 @@ async fn handle_request(
 
     // Sanitize request body (input to LLM)
     let sanitized_input = if !body_str.is_empty() {
-        match sanitize_llm_request(&state.guard, &body_str).await {
+        match sanitize_llm_request(guard, ctx, &body_str).await {
             Ok(sanitized) => sanitized,
             Err(e) => {
                 return Ok(error_response(
@@ -52,7 +136,11 @@ async fn handle_request(
     };
 
     // Build upstream URL
-    let upstream_url = format!("{}{}", state.upstream, uri.path_and_query().map(|p| p.as_str()).unwrap_or("/"));
+    let upstream_url = format!(
+        "{}{}",
+        state.upstream,
+        uri.path_and_query().map(|p| p.as_str()).unwrap_or("/")
+    );
 
     // Forward to upstream
     let mut upstream_req = state.client.request(method, &upstream_url);
@@ -90,7 +178,7 @@ async fn handle_request(
     // Sanitize response body (output from LLM)
     let resp_str = String::from_utf8_lossy(&resp_body);
     let sanitized_output = if !resp_str.is_empty() {
-        match sanitize_llm_response(&state.guard, &resp_str).await {
+        match sanitize_llm_response(guard, ctx, &resp_str).await {
             Ok(sanitized) => sanitized,
             Err(e) => {
                 return Ok(error_response(
@@ -117,15 +205,19 @@ async fn handle_request(
 }
 
 /// Sanitize LLM request body (user input)
-async fn sanitize_llm_request(guard: &Guard, body: &str) -> Result<String, String> {
+async fn sanitize_llm_request(
+    guard: &Guard,
+    ctx: &GuardContext,
+    body: &str,
+) -> Result<String, String> {
     // Try to parse as JSON and sanitize message content
     if let Ok(mut json) = serde_json::from_str::<Value>(body) {
-        sanitize_json_messages(guard, &mut json, true).await?;
+        sanitize_json_messages(guard, ctx, &mut json, true).await?;
         return Ok(serde_json::to_string(&json).unwrap_or_else(|_| body.to_string()));
     }
 
     // Plain text - sanitize directly
-    match guard.sanitize_input(body).await {
+    match guard.sanitize_input_with_context(body, ctx).await {
         Ok(SanitizeResult::Clean(text)) => Ok(text),
         Ok(SanitizeResult::Redacted { text, .. }) => Ok(text),
         Ok(SanitizeResult::Blocked { reason, .. }) => Err(reason),
@@ -134,15 +226,19 @@ async fn sanitize_llm_request(guard: &Guard, body: &str) -> Result<String, Strin
 }
 
 /// Sanitize LLM response body (model output)
-async fn sanitize_llm_response(guard: &Guard, body: &str) -> Result<String, String> {
+async fn sanitize_llm_response(
+    guard: &Guard,
+    ctx: &GuardContext,
+    body: &str,
+) -> Result<String, String> {
     // Try to parse as JSON and sanitize message content
     if let Ok(mut json) = serde_json::from_str::<Value>(body) {
-        sanitize_json_messages(guard, &mut json, false).await?;
+        sanitize_json_messages(guard, ctx, &mut json, false).await?;
         return Ok(serde_json::to_string(&json).unwrap_or_else(|_| body.to_string()));
     }
 
     // Plain text - sanitize directly
-    match guard.sanitize_output(body).await {
+    match guard.sanitize_output_with_context(body, ctx).await {
         Ok(SanitizeResult::Clean(text)) => Ok(text),
         Ok(SanitizeResult::Redacted { text, .. }) => Ok(text),
         Ok(SanitizeResult::Blocked { reason, .. }) => Err(reason),
@@ -151,7 +247,12 @@ async fn sanitize_llm_response(guard: &Guard, body: &str) -> Result<String, Stri
 }
 
 /// Recursively sanitize message content in JSON (OpenAI/Anthropic format)
-async fn sanitize_json_messages(guard: &Guard, json: &mut Value, is_input: bool) -> Result<(), String> {
+async fn sanitize_json_messages(
+    guard: &Guard,
+    ctx: &GuardContext,
+    json: &mut Value,
+    is_input: bool,
+) -> Result<(), String> {
     match json {
         Value::Object(map) => {
             // OpenAI format: messages[].content
@@ -159,9 +260,9 @@ async fn sanitize_json_messages(guard: &Guard, json: &mut Value, is_input: bool)
             if let Some(content) = map.get_mut("content") {
                 if let Value::String(text) = content {
                     let sanitized = if is_input {
-                        guard.sanitize_input(text).await
+                        guard.sanitize_input_with_context(text, ctx).await
                     } else {
-                        guard.sanitize_output(text).await
+                        guard.sanitize_output_with_context(text, ctx).await
                     };
                     match sanitized {
                         Ok(SanitizeResult::Clean(t)) => *text = t,
@@ -171,7 +272,7 @@ async fn sanitize_json_messages(guard: &Guard, json: &mut Value, is_input: bool)
                     }
                 } else if let Value::Array(arr) = content {
                     for item in arr {
-                        Box::pin(sanitize_json_messages(guard, item, is_input)).await?;
+                        Box::pin(sanitize_json_messages(guard, ctx, item, is_input)).await?;
                     }
                 }
             }
@@ -179,9 +280,9 @@ async fn sanitize_json_messages(guard: &Guard, json: &mut Value, is_input: bool)
             // Anthropic content block: text field
             if let Some(Value::String(text)) = map.get_mut("text") {
                 let sanitized = if is_input {
-                    guard.sanitize_input(text).await
+                    guard.sanitize_input_with_context(text, ctx).await
                 } else {
-                    guard.sanitize_output(text).await
+                    guard.sanitize_output_with_context(text, ctx).await
                 };
                 match sanitized {
                     Ok(SanitizeResult::Clean(t)) => *text = t,
@@ -193,21 +294,21 @@ async fn sanitize_json_messages(guard: &Guard, json: &mut Value, is_input: bool)
 
             // Recurse into other fields
             if let Some(messages) = map.get_mut("messages") {
-                Box::pin(sanitize_json_messages(guard, messages, is_input)).await?;
+                Box::pin(sanitize_json_messages(guard, ctx, messages, is_input)).await?;
             }
             if let Some(choices) = map.get_mut("choices") {
-                Box::pin(sanitize_json_messages(guard, choices, is_input)).await?;
+                Box::pin(sanitize_json_messages(guard, ctx, choices, is_input)).await?;
             }
             if let Some(message) = map.get_mut("message") {
-                Box::pin(sanitize_json_messages(guard, message, is_input)).await?;
+                Box::pin(sanitize_json_messages(guard, ctx, message, is_input)).await?;
             }
             if let Some(delta) = map.get_mut("delta") {
-                Box::pin(sanitize_json_messages(guard, delta, is_input)).await?;
+                Box::pin(sanitize_json_messages(guard, ctx, delta, is_input)).await?;
             }
         }
         Value::Array(arr) => {
             for item in arr {
-                Box::pin(sanitize_json_messages(guard, item, is_input)).await?;
+                Box::pin(sanitize_json_messages(guard, ctx, item, is_input)).await?;
             }
         }
         _ => {}
@@ -236,6 +337,7 @@ async fn main() {
     // Parse args
     let mut upstream = String::from("https://api.openai.com");
     let mut port: u16 = 8080;
+    let mut config_path: Option<PathBuf> = None;
 
     let mut i = 1;
     while i < args.len() {
@@ -256,6 +358,14 @@ async fn main() {
                     i += 1;
                 }
             }
+            "--config" | "-c" => {
+                if i + 1 < args.len() {
+                    config_path = Some(PathBuf::from(&args[i + 1]));
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
             "--help" | "-h" => {
                 println!("guard-proxy - LLM API sanitization proxy");
                 println!();
@@ -263,8 +373,12 @@ async fn main() {
                 println!("    guard-proxy [OPTIONS]");
                 println!();
                 println!("OPTIONS:");
-                println!("    -u, --upstream <URL>   Upstream API URL (default: https://api.openai.com)");
+                println!(
+                    "    -u, --upstream <URL>   Upstream API URL (default: https://api.openai.com)"
+                );
                 println!("    -p, --port <PORT>      Listen port (default: 8080)");
+                println!("    -c, --config <FILE>    Load config from TOML/YAML/JSON file");
+                println!("                           (reloaded on change and on SIGHUP)");
                 println!("    -h, --help             Print help");
                 println!();
                 println!("EXAMPLES:");
@@ -272,18 +386,38 @@ async fn main() {
                 println!("    guard-proxy --upstream https://api.anthropic.com --port 8081");
                 println!();
                 println!("Then set OPENAI_BASE_URL=http://localhost:8080 in your client.");
+                println!();
+                println!("Config values can be overridden with HANZO_GUARD_<SECTION>__<FIELD>,");
+                println!("e.g. HANZO_GUARD_PII__DETECT_EMAIL=false");
                 return;
             }
             _ => i += 1,
         }
     }
 
+    let guard = match GuardConfig::load(config_path.as_deref())
+        .and_then(|config| Guard::builder().with_config(config).try_build())
+    {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+
     let state = Arc::new(ProxyState {
-        guard: Guard::new(GuardConfig::default()),
+        guard: RwLock::new(Arc::new(guard)),
+        config_path,
         upstream,
         client: reqwest::Client::new(),
     });
 
+    if let Some(path) = state.config_path.clone() {
+        tokio::spawn(watch_config(state.clone(), path));
+        #[cfg(unix)]
+        tokio::spawn(reload_on_sighup(state.clone()));
+    }
+
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     let listener = TcpListener::bind(addr).await.unwrap();
 
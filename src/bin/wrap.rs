@@ -4,12 +4,14 @@
 //!   guard-wrap claude
 //!   guard-wrap codex
 //!   guard-wrap -- python script.py
+//!   guard-wrap --config guard.toml claude
 //!
 //! Wraps any CLI command and filters stdin/stdout through guard in real-time.
 
 use hanzo_guard::{Guard, GuardConfig, SanitizeResult};
 use portable_pty::{native_pty_system, CommandBuilder, PtySize};
 use std::io::{Read, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
@@ -27,9 +29,15 @@ async fn filter_text(guard: &Guard, text: &str, is_input: bool) -> String {
 
     match result {
         Ok(SanitizeResult::Clean(t)) => t,
-        Ok(SanitizeResult::Redacted { text: t, redactions }) => {
+        Ok(SanitizeResult::Redacted {
+            text: t,
+            redactions,
+        }) => {
             if !redactions.is_empty() {
-                eprintln!("\x1b[33m[guard] Redacted {} item(s)\x1b[0m", redactions.len());
+                eprintln!(
+                    "\x1b[33m[guard] Redacted {} item(s)\x1b[0m",
+                    redactions.len()
+                );
             }
             t
         }
@@ -51,10 +59,11 @@ fn main() {
         println!("guard-wrap - PTY wrapper with I/O sanitization");
         println!();
         println!("USAGE:");
-        println!("    guard-wrap <COMMAND> [ARGS...]");
+        println!("    guard-wrap [OPTIONS] <COMMAND> [ARGS...]");
         println!();
         println!("OPTIONS:");
-        println!("    -h, --help    Print help");
+        println!("    -c, --config <FILE>    Load config from TOML/YAML/JSON file");
+        println!("    -h, --help             Print help");
         println!();
         println!("EXAMPLES:");
         println!("    guard-wrap claude");
@@ -66,12 +75,22 @@ fn main() {
         return;
     }
 
-    // Skip -- if present
-    let cmd_start = if args.get(1).map(|s| s.as_str()) == Some("--") {
-        2
-    } else {
-        1
-    };
+    // Parse leading options, then skip -- if present
+    let mut cmd_start = 1;
+    let mut config_path: Option<PathBuf> = None;
+    while let Some(arg) = args.get(cmd_start) {
+        match arg.as_str() {
+            "--config" | "-c" => {
+                config_path = args.get(cmd_start + 1).map(PathBuf::from);
+                cmd_start += 2;
+            }
+            "--" => {
+                cmd_start += 1;
+                break;
+            }
+            _ => break,
+        }
+    }
 
     if cmd_start >= args.len() {
         eprintln!("No command specified");
@@ -85,7 +104,15 @@ fn main() {
     let rt = tokio::runtime::Runtime::new().unwrap();
 
     // Initialize guard
-    let guard = Arc::new(Guard::new(GuardConfig::default()));
+    let guard = match GuardConfig::load(config_path.as_deref())
+        .and_then(|config| Guard::builder().with_config(config).try_build())
+    {
+        Ok(guard) => Arc::new(guard),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
 
     // Create PTY
     let pty_system = native_pty_system();
@@ -105,7 +132,10 @@ fn main() {
     }
 
     // Spawn child process
-    let mut child = pair.slave.spawn_command(cmd).expect("Failed to spawn command");
+    let mut child = pair
+        .slave
+        .spawn_command(cmd)
+        .expect("Failed to spawn command");
 
     // Get PTY master for I/O
     let master = pair.master;
@@ -139,7 +169,9 @@ fn main() {
 
     // PTY reader thread (sync -> async)
     let stdout_tx_clone = stdout_tx;
-    let mut reader = master.try_clone_reader().expect("Failed to clone PTY reader");
+    let mut reader = master
+        .try_clone_reader()
+        .expect("Failed to clone PTY reader");
     std::thread::spawn(move || {
         let mut buffer = [0u8; 4096];
         loop {
@@ -1,9 +1,15 @@
 //! Configuration for Hanzo Guard
 
+use crate::error::{GuardError, Result};
+use crate::types::RedactionType;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
 
 /// Main configuration for Guard
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct GuardConfig {
     /// PII detection configuration
     pub pii: PiiConfig,
@@ -69,13 +75,464 @@ impl GuardConfig {
             },
         }
     }
+
+    /// Load configuration from a TOML, YAML or JSON file
+    ///
+    /// The format is chosen by file extension. Fields missing from the file
+    /// keep their default values.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let config = Self::read_file(path.as_ref())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load the default configuration with `HANZO_GUARD_*` environment overrides
+    pub fn from_env() -> Result<Self> {
+        Self::default().with_overrides(std::env::vars())
+    }
+
+    /// Load configuration in layers: defaults, then the optional file, then
+    /// `HANZO_GUARD_*` environment overrides
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let base = match path {
+            Some(path) => Self::read_file(path)?,
+            None => Self::default(),
+        };
+        base.with_overrides(std::env::vars())
+    }
+
+    /// Apply overrides of the form `HANZO_GUARD_<SECTION>__<FIELD>=value`
+    ///
+    /// Keys are matched case-insensitively against the serialized field
+    /// names, so `HANZO_GUARD_PII__DETECT_EMAIL=false` sets
+    /// `pii.detect_email`. Variables without a `__` section separator are
+    /// ignored. List fields accept either a JSON array or a comma-separated
+    /// string. The resulting configuration is validated.
+    pub fn with_overrides<I, K, V>(self, vars: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut root = serde_json::to_value(&self)?;
+
+        for (key, value) in vars {
+            let Some(path) = key.as_ref().strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            if !path.contains(ENV_SEPARATOR) {
+                continue;
+            }
+            apply_override(&mut root, path, value.as_ref())?;
+        }
+
+        let config: Self = serde_json::from_value(root)
+            .map_err(|e| GuardError::ConfigError(format!("invalid override: {e}")))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check the configuration for values the detectors cannot work with
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = vec![];
+
+        if self.pii.redaction_format.is_empty() {
+            problems.push("pii.redaction_format must not be empty".to_string());
+        }
+        if self.pii.uses_strategy(|s| s.is_keyed())
+            && self
+                .pii
+                .pseudonym_secret
+                .as_deref()
+                .unwrap_or("")
+                .is_empty()
+        {
+            problems.push(
+                "pii.pseudonym_secret must be set for the pseudonym, hash and format_preserving strategies"
+                    .to_string(),
+            );
+        }
+        if self.pii.entropy_threshold <= 0.0 || self.pii.entropy_threshold > 6.0 {
+            problems.push(format!(
+                "pii.entropy_threshold must be between 0.0 and 6.0 bits (got {})",
+                self.pii.entropy_threshold
+            ));
+        }
+        if self.pii.entropy_min_length < 8 {
+            problems.push("pii.entropy_min_length must be at least 8".to_string());
+        }
+        for dictionary in &self.pii.dictionaries {
+            if dictionary.name.is_empty() {
+                problems.push("pii.dictionaries entries must have a name".to_string());
+            } else if dictionary.path.is_none() && dictionary.terms.is_empty() {
+                problems.push(format!(
+                    "pii.dictionaries '{}' needs a path or terms",
+                    dictionary.name
+                ));
+            } else if let Some(path) = &dictionary.path {
+                if let Err(e) = check_readable(Path::new(path)) {
+                    problems.push(format!(
+                        "pii.dictionaries '{}' cannot read {path}: {e}",
+                        dictionary.name
+                    ));
+                }
+            }
+        }
+        for rule in &self.pii.custom_rules {
+            if rule.name.is_empty() {
+                problems.push("pii.custom_rules entries must have a name".to_string());
+            } else if rule.regex.is_empty() {
+                problems.push(format!("pii.custom_rules '{}' needs a regex", rule.name));
+            }
+        }
+        #[cfg(feature = "pii")]
+        problems.extend(crate::pii::config_problems(&self.pii));
+        for (redaction_type, min) in &self.pii.min_confidence {
+            if !(0.0..=1.0).contains(min) {
+                problems.push(format!(
+                    "pii.min_confidence for {redaction_type} must be between 0.0 and 1.0 (got {min})"
+                ));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.injection.sensitivity) {
+            problems.push(format!(
+                "injection.sensitivity must be between 0.0 and 1.0 (got {})",
+                self.injection.sensitivity
+            ));
+        }
+        if self.content_filter.enabled && self.content_filter.api_endpoint.is_empty() {
+            problems.push("content_filter.api_endpoint must be set when enabled".to_string());
+        }
+        if self.content_filter.timeout_ms == 0 {
+            problems.push("content_filter.timeout_ms must be greater than 0".to_string());
+        }
+        if self.rate_limit.enabled {
+            if self.rate_limit.requests_per_minute == 0 {
+                problems.push("rate_limit.requests_per_minute must be greater than 0".to_string());
+            }
+            if self.rate_limit.burst_size == 0 {
+                problems.push("rate_limit.burst_size must be greater than 0".to_string());
+            }
+        }
+        if self.audit.log_file.as_deref() == Some("") {
+            problems.push("audit.log_file must not be empty".to_string());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(GuardError::ConfigError(problems.join("; ")))
+        }
+    }
+
+    /// Parse a config file without validating it
+    fn read_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            GuardError::ConfigError(format!("failed to read {}: {e}", path.display()))
+        })?;
+
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+
+        let parsed = match extension.as_deref() {
+            Some("toml") => toml::from_str(&contents).map_err(|e| e.to_string()),
+            Some("yaml") | Some("yml") => {
+                serde_yaml::from_str(&contents).map_err(|e| e.to_string())
+            }
+            Some("json") => serde_json::from_str(&contents).map_err(|e| e.to_string()),
+            _ => {
+                return Err(GuardError::ConfigError(format!(
+                    "unsupported config format: {} (expected .toml, .yaml, .yml or .json)",
+                    path.display()
+                )))
+            }
+        };
+
+        parsed.map_err(|e| GuardError::ConfigError(format!("{}: {e}", path.display())))
+    }
+}
+
+/// Prefix for environment variable overrides
+pub const ENV_PREFIX: &str = "HANZO_GUARD_";
+
+/// Separator between nested keys in environment variable overrides
+const ENV_SEPARATOR: &str = "__";
+
+/// Whether `path` is a file that can be opened for reading
+fn check_readable(path: &Path) -> std::io::Result<()> {
+    let file = std::fs::File::open(path)?;
+    if file.metadata()?.is_dir() {
+        return Err(std::io::Error::other("is a directory"));
+    }
+    Ok(())
+}
+
+/// Set a single dotted override on the serialized config
+fn apply_override(root: &mut Value, path: &str, raw: &str) -> Result<()> {
+    let keys: Vec<String> = path
+        .split(ENV_SEPARATOR)
+        .map(|k| k.to_ascii_lowercase())
+        .collect();
+    let unknown = || GuardError::ConfigError(format!("unknown config key: {ENV_PREFIX}{path}"));
+
+    let mut node = root;
+    for key in &keys {
+        node = node.get_mut(key.as_str()).ok_or_else(unknown)?;
+    }
+
+    *node = parse_override(node, raw).map_err(|reason| {
+        GuardError::ConfigError(format!("invalid value for {ENV_PREFIX}{path}: {reason}"))
+    })?;
+    Ok(())
+}
+
+/// Parse a raw override using the type of the value it replaces
+fn parse_override(current: &Value, raw: &str) -> std::result::Result<Value, String> {
+    let raw = raw.trim();
+    match current {
+        Value::Bool(_) => match raw.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(Value::Bool(true)),
+            "false" | "0" | "no" | "off" => Ok(Value::Bool(false)),
+            _ => Err(format!("expected a boolean, got {raw:?}")),
+        },
+        Value::Number(_) => match serde_json::from_str::<Value>(raw) {
+            Ok(value @ Value::Number(_)) => Ok(value),
+            _ => Err(format!("expected a number, got {raw:?}")),
+        },
+        Value::Array(_) if raw.starts_with('[') => {
+            serde_json::from_str(raw).map_err(|e| e.to_string())
+        }
+        Value::Array(_) => Ok(Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| Value::String(s.to_string()))
+                .collect(),
+        )),
+        Value::Object(_) => serde_json::from_str(raw).map_err(|e| e.to_string()),
+        Value::Null if raw.is_empty() => Ok(Value::Null),
+        Value::String(_) | Value::Null => Ok(Value::String(raw.to_string())),
+    }
+}
+
+/// Maps keyed by [`RedactionType`], written with string keys so they work
+/// as TOML tables and JSON objects: `Email` for built-in types and the
+/// dictionary or rule name for [`RedactionType::Custom`]
+mod type_keys {
+    use crate::types::RedactionType;
+    use serde::de::IntoDeserializer;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+
+    pub fn serialize<S, V>(
+        map: &HashMap<RedactionType, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        V: Serialize,
+    {
+        serializer.collect_map(
+            map.iter()
+                .map(|(redaction_type, value)| (key(redaction_type), value)),
+        )
+    }
+
+    pub fn deserialize<'de, D, V>(deserializer: D) -> Result<HashMap<RedactionType, V>, D::Error>
+    where
+        D: Deserializer<'de>,
+        V: Deserialize<'de>,
+    {
+        let map = HashMap::<String, V>::deserialize(deserializer)?;
+        Ok(map
+            .into_iter()
+            .map(|(key, value)| (redaction_type(key), value))
+            .collect())
+    }
+
+    fn key(redaction_type: &RedactionType) -> String {
+        match serde_json::to_value(redaction_type) {
+            Ok(serde_json::Value::String(name)) => name,
+            _ => redaction_type.token_label().to_string(),
+        }
+    }
+
+    /// Built-in type with this variant name, or else a custom label
+    fn redaction_type(key: String) -> RedactionType {
+        let de: serde::de::value::StrDeserializer<serde::de::value::Error> =
+            key.as_str().into_deserializer();
+        RedactionType::deserialize(de).unwrap_or(RedactionType::Custom(key))
+    }
+}
+
+/// How a pipeline stage applies its decisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StageMode {
+    /// Redact or block as detected
+    #[default]
+    Enforce,
+    /// Record what would have happened but pass the text through untouched
+    Monitor,
+}
+
+/// How detected PII is replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionStrategy {
+    /// Replace with `redaction_format`, e.g. `[REDACTED:Email]`
+    #[default]
+    Placeholder,
+    /// Replace with a per-request token such as `<EMAIL_1>` that is
+    /// restored when output is sanitized with the same context
+    Token,
+    /// Replace with a realistic fake that stays the same for the whole
+    /// session (keyed by `pseudonym_secret`)
+    Pseudonym,
+    /// Replace every letter and digit with `*`, keeping separators
+    Mask,
+    /// Keep the parts that are safe to show, e.g. `****-****-****-0366`,
+    /// `j***@example.com` or `192.168.1.0/24`
+    PartialMask,
+    /// Replace with a keyed hash (keyed by `pseudonym_secret`)
+    Hash,
+    /// Remove the value entirely
+    Drop,
+    /// Replace digits with digits and letters with letters, keyed by
+    /// `pseudonym_secret`
+    FormatPreserving,
+}
+
+impl RedactionStrategy {
+    /// Whether the strategy is keyed by `pseudonym_secret`
+    pub fn is_keyed(&self) -> bool {
+        matches!(
+            self,
+            RedactionStrategy::Pseudonym
+                | RedactionStrategy::Hash
+                | RedactionStrategy::FormatPreserving
+        )
+    }
+}
+
+/// Country- or region-specific PII detector packs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// IBANs and national IDs (ES DNI/NIE, NL BSN, PL PESEL, FR NIR)
+    Eu,
+    /// IBANs and National Insurance numbers
+    Gb,
+    /// Social Insurance Numbers
+    Ca,
+    /// Aadhaar and PAN
+    In,
+}
+
+/// Reserved IP ranges that can be exempted from redaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpRange {
+    /// RFC 1918, carrier-grade NAT (100.64.0.0/10) and unique local IPv6
+    Private,
+    /// 127.0.0.0/8 and ::1
+    Loopback,
+    /// 169.254.0.0/16 and fe80::/10
+    LinkLocal,
+    /// TEST-NET-1/2/3 and 2001:db8::/32
+    Documentation,
 }
 
+/// What happens when a dictionary term is found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DictionaryAction {
+    /// Redact the term like any other PII
+    #[default]
+    Redact,
+    /// Block the content entirely
+    Block,
+}
+
+/// A customer-defined list of sensitive terms (codenames, customers, hosts)
+///
+/// Matching ignores case and whitespace differences and only matches whole words.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictionaryConfig {
+    /// Label for matches, reported as `RedactionType::Custom(name)`
+    pub name: String,
+    /// Word-list file with one term per line; blank lines and `#` comments are skipped
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Terms listed inline, in addition to the file
+    #[serde(default)]
+    pub terms: Vec<String>,
+    /// Redact matches or block the content
+    #[serde(default)]
+    pub action: DictionaryAction,
+}
+
+/// Checksum a custom rule's matches must pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleValidator {
+    /// Luhn mod 10, as used by card numbers and Canadian SINs
+    Luhn,
+    /// IBAN mod 97
+    Iban,
+    /// Verhoeff, as used by Aadhaar numbers
+    Verhoeff,
+}
+
+/// A user-supplied PII regex
+///
+/// Matches are reported as `RedactionType::Custom(name)`. If the regex has
+/// capture groups, only the first group that matched is redacted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomRuleConfig {
+    /// Label for matches
+    pub name: String,
+    /// Pattern to detect
+    pub regex: String,
+    /// Checksum the redacted part must pass
+    #[serde(default)]
+    pub validator: Option<RuleValidator>,
+    /// Placeholder used instead of `redaction_format` (use {TYPE} for the name)
+    #[serde(default)]
+    pub replacement: Option<String>,
+}
+
+/// Values that are never redacted, such as documentation examples and your
+/// own support address
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AllowConfig {
+    /// Exact values; case is ignored, and so are spaces and dashes in numbers
+    pub values: Vec<String>,
+    /// Regexes a whole value must match
+    pub patterns: Vec<String>,
+    /// Email domains, including their subdomains
+    pub domains: Vec<String>,
+    /// IP ranges in CIDR notation, e.g. `127.0.0.0/8`
+    pub cidrs: Vec<String>,
+    /// `GuardContext::metadata` fields whose values are allowed for that
+    /// request only, e.g. the caller's own email address
+    pub metadata_keys: Vec<String>,
+}
+
+/// Confidence required of types missing from `PiiConfig::min_confidence`
+pub const DEFAULT_MIN_CONFIDENCE: f32 = 0.5;
+
 /// PII detection configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PiiConfig {
     /// Enable PII detection
     pub enabled: bool,
+    /// Enforce redactions or only monitor them
+    pub mode: StageMode,
     /// Detect SSNs
     pub detect_ssn: bool,
     /// Detect credit cards
@@ -86,32 +543,177 @@ pub struct PiiConfig {
     pub detect_phone: bool,
     /// Detect IP addresses
     pub detect_ip: bool,
+    /// IP ranges whose addresses are left alone, e.g. private and loopback
+    /// addresses that identify no one
+    pub skip_ip_ranges: Vec<IpRange>,
     /// Detect API keys/secrets
     pub detect_api_keys: bool,
+    /// Detect passwords in key/value pairs, CLI flags, .netrc and prose
+    pub detect_passwords: bool,
+    /// Detect credentials and signatures in URLs, keeping host and path
+    pub detect_urls: bool,
+    /// Detect Bitcoin and Ethereum wallet addresses (opt-in)
+    pub detect_crypto_wallets: bool,
+    /// Detect MAC addresses (opt-in)
+    pub detect_mac_addresses: bool,
+    /// Detect IMEIs (opt-in)
+    pub detect_imei: bool,
+    /// Detect UUID-form device and advertising IDs (opt-in)
+    pub detect_device_ids: bool,
+    /// Locale packs to enable on top of the default (US-centric) detectors
+    pub locales: Vec<Locale>,
+    /// Undo obfuscation before matching: NFKC, zero-width characters,
+    /// "at"/"dot" spellings and spaced-out characters
+    pub normalize: bool,
+    /// Classify values in JSON, CSV, YAML and key=value data by their field
+    /// or column name (opt-in)
+    pub detect_structured: bool,
+    /// Detect person names (opt-in, requires the `ner` feature)
+    pub detect_names: bool,
+    /// Detect street addresses (opt-in, requires the `ner` feature)
+    pub detect_addresses: bool,
+    /// Detect dates of birth (opt-in, requires the `ner` feature)
+    pub detect_dates_of_birth: bool,
+    /// Detect unknown secrets by their Shannon entropy (opt-in)
+    pub detect_high_entropy: bool,
+    /// Bits per character a base64-like string needs next to a context
+    /// keyword; without one it needs half a bit more. Hex strings are only
+    /// flagged next to a keyword, at three quarters of this value.
+    pub entropy_threshold: f64,
+    /// Minimum length of a high-entropy candidate
+    pub entropy_min_length: usize,
+    /// Words that mark a nearby string as a likely secret
+    pub entropy_keywords: Vec<String>,
+    /// Values never flagged as high-entropy secrets (UUIDs and git SHAs are
+    /// always skipped)
+    pub entropy_allowlist: Vec<String>,
     /// Redaction placeholder format (use {TYPE} for type name)
     pub redaction_format: String,
+    /// How detected values are replaced
+    pub strategy: RedactionStrategy,
+    /// Per-type overrides of `strategy`
+    #[serde(with = "type_keys")]
+    pub strategies: HashMap<RedactionType, RedactionStrategy>,
+    /// HMAC secret for the pseudonym, hash and format-preserving strategies
+    pub pseudonym_secret: Option<String>,
+    /// Customer-defined term lists
+    pub dictionaries: Vec<DictionaryConfig>,
+    /// User-supplied regex rules, checked before the built-in detectors
+    pub custom_rules: Vec<CustomRuleConfig>,
+    /// Exceptions that are never redacted
+    pub allow: AllowConfig,
+    /// Per-type confidence a match needs to be redacted (0.0 - 1.0), after
+    /// context keywords are applied; unlisted types use 0.5
+    #[serde(with = "type_keys")]
+    pub min_confidence: HashMap<RedactionType, f32>,
+}
+
+impl PiiConfig {
+    /// Strategy used for a type of PII
+    pub fn strategy_for(&self, redaction_type: &RedactionType) -> RedactionStrategy {
+        self.strategies
+            .get(redaction_type)
+            .copied()
+            .unwrap_or(self.strategy)
+    }
+
+    /// Confidence a match of a type needs to be redacted
+    pub fn min_confidence_for(&self, redaction_type: &RedactionType) -> f32 {
+        self.min_confidence
+            .get(redaction_type)
+            .copied()
+            .unwrap_or(DEFAULT_MIN_CONFIDENCE)
+    }
+
+    /// Whether detection of a type is turned on
+    pub fn detects(&self, redaction_type: &RedactionType) -> bool {
+        match redaction_type {
+            RedactionType::Ssn => self.detect_ssn,
+            RedactionType::CreditCard => self.detect_credit_card,
+            RedactionType::Email => self.detect_email,
+            RedactionType::Phone => self.detect_phone,
+            RedactionType::IpAddress => self.detect_ip,
+            RedactionType::ApiKey => self.detect_api_keys,
+            RedactionType::Password => self.detect_passwords,
+            RedactionType::Url | RedactionType::Credential => self.detect_urls,
+            RedactionType::CryptoWallet => self.detect_crypto_wallets,
+            RedactionType::MacAddress => self.detect_mac_addresses,
+            RedactionType::Imei => self.detect_imei,
+            RedactionType::DeviceId => self.detect_device_ids,
+            RedactionType::PersonName => self.detect_names,
+            RedactionType::Address => self.detect_addresses,
+            RedactionType::DateOfBirth => self.detect_dates_of_birth,
+            _ => true,
+        }
+    }
+
+    /// Whether any type uses a strategy matching the predicate
+    pub fn uses_strategy(&self, predicate: impl Fn(RedactionStrategy) -> bool) -> bool {
+        predicate(self.strategy) || self.strategies.values().any(|s| predicate(*s))
+    }
 }
 
 impl Default for PiiConfig {
     fn default() -> Self {
         Self {
             enabled: true,
+            mode: StageMode::Enforce,
             detect_ssn: true,
             detect_credit_card: true,
             detect_email: true,
             detect_phone: true,
             detect_ip: true,
+            skip_ip_ranges: vec![],
             detect_api_keys: true,
+            detect_passwords: true,
+            detect_urls: true,
+            detect_crypto_wallets: false,
+            detect_mac_addresses: false,
+            detect_imei: false,
+            detect_device_ids: false,
+            locales: vec![],
+            normalize: true,
+            detect_structured: false,
+            detect_names: false,
+            detect_addresses: false,
+            detect_dates_of_birth: false,
+            detect_high_entropy: false,
+            entropy_threshold: 4.0,
+            entropy_min_length: 20,
+            entropy_keywords: [
+                "token",
+                "secret",
+                "password",
+                "passwd",
+                "pwd",
+                "key",
+                "auth",
+                "credential",
+                "bearer",
+            ]
+            .map(String::from)
+            .to_vec(),
+            entropy_allowlist: vec![],
             redaction_format: "[REDACTED:{TYPE}]".to_string(),
+            strategy: RedactionStrategy::Placeholder,
+            strategies: HashMap::new(),
+            pseudonym_secret: None,
+            min_confidence: HashMap::new(),
+            dictionaries: vec![],
+            custom_rules: vec![],
+            allow: AllowConfig::default(),
         }
     }
 }
 
 /// Injection detection configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct InjectionConfig {
     /// Enable injection detection
     pub enabled: bool,
+    /// Enforce blocks or only monitor them
+    pub mode: StageMode,
     /// Block on detection (vs. just warn)
     pub block_on_detection: bool,
     /// Sensitivity level (0.0-1.0)
@@ -124,6 +726,7 @@ impl Default for InjectionConfig {
     fn default() -> Self {
         Self {
             enabled: true,
+            mode: StageMode::Enforce,
             block_on_detection: true,
             sensitivity: 0.7,
             custom_patterns: vec![],
@@ -133,9 +736,12 @@ impl Default for InjectionConfig {
 
 /// Content filter configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ContentFilterConfig {
     /// Enable content filtering
     pub enabled: bool,
+    /// Enforce blocks or only monitor them
+    pub mode: StageMode,
     /// Zen Guard API endpoint
     pub api_endpoint: String,
     /// API key for Zen Guard
@@ -152,6 +758,7 @@ impl Default for ContentFilterConfig {
     fn default() -> Self {
         Self {
             enabled: false, // Disabled by default as it requires API
+            mode: StageMode::Enforce,
             api_endpoint: "https://api.zenlm.ai/v1/guard".to_string(),
             api_key: None,
             block_controversial: false,
@@ -169,9 +776,12 @@ impl Default for ContentFilterConfig {
 
 /// Rate limiting configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RateLimitConfig {
     /// Enable rate limiting
     pub enabled: bool,
+    /// Enforce limits or only monitor them
+    pub mode: StageMode,
     /// Requests per minute per user
     pub requests_per_minute: u32,
     /// Tokens per minute per user
@@ -184,6 +794,7 @@ impl Default for RateLimitConfig {
     fn default() -> Self {
         Self {
             enabled: true,
+            mode: StageMode::Enforce,
             requests_per_minute: 60,
             tokens_per_minute: 100_000,
             burst_size: 10,
@@ -193,6 +804,7 @@ impl Default for RateLimitConfig {
 
 /// Audit logging configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AuditConfig {
     /// Enable audit logging
     pub enabled: bool,
@@ -214,3 +826,230 @@ impl Default for AuditConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(extension: &str, contents: &str) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("hanzo-guard-{}.{extension}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_toml_file() {
+        let path = write_temp(
+            "toml",
+            "[pii]\ndetect_email = false\n\n[injection]\nsensitivity = 0.9\n",
+        );
+        let config = GuardConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!config.pii.detect_email);
+        assert!(config.pii.detect_ssn);
+        assert_eq!(config.injection.sensitivity, 0.9);
+    }
+
+    #[test]
+    fn test_from_yaml_file() {
+        let path = write_temp("yaml", "rate_limit:\n  requests_per_minute: 5\n");
+        let config = GuardConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.rate_limit.requests_per_minute, 5);
+        assert_eq!(config.rate_limit.burst_size, 10);
+    }
+
+    #[test]
+    fn test_invalid_file_is_config_error() {
+        let path = write_temp("toml", "[injection]\nsensitivity = 3.0\n");
+        let result = GuardConfig::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(GuardError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_min_confidence() {
+        let path = write_temp("toml", "[pii.min_confidence]\nSsn = 0.9\n");
+        let config = GuardConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.pii.min_confidence_for(&RedactionType::Ssn), 0.9);
+        assert_eq!(
+            config.pii.min_confidence_for(&RedactionType::Email),
+            DEFAULT_MIN_CONFIDENCE
+        );
+
+        let path = write_temp("toml", "[pii.min_confidence]\nPhone = 1.5\n");
+        let result = GuardConfig::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(GuardError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let path = write_temp(
+            "toml",
+            "[[pii.custom_rules]]\nname = \"EMPLOYEE_ID\"\nregex = 'E-\\d{6}'\nvalidator = \"luhn\"\n",
+        );
+        let config = GuardConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.pii.custom_rules[0].regex, r"E-\d{6}");
+        assert_eq!(
+            config.pii.custom_rules[0].validator,
+            Some(RuleValidator::Luhn)
+        );
+
+        let mut config = GuardConfig::default();
+        config.pii.custom_rules.push(CustomRuleConfig {
+            name: "EMPTY".to_string(),
+            regex: String::new(),
+            validator: None,
+            replacement: None,
+        });
+        assert!(config.validate().is_err());
+
+        #[cfg(feature = "pii")]
+        {
+            config.pii.custom_rules[0].name = "BROKEN".to_string();
+            config.pii.custom_rules[0].regex = "(unclosed".to_string();
+            match config.validate() {
+                Err(GuardError::ConfigError(message)) => assert!(message.contains("BROKEN")),
+                other => panic!("expected a config error, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_unreadable_dictionary() {
+        let mut config = GuardConfig::default();
+        config.pii.dictionaries.push(DictionaryConfig {
+            name: "CODENAME".to_string(),
+            path: Some("/nonexistent/codenames.txt".to_string()),
+            terms: vec![],
+            action: DictionaryAction::Redact,
+        });
+        match config.validate() {
+            Err(GuardError::ConfigError(message)) => {
+                assert!(message.contains("/nonexistent/codenames.txt"), "{message}")
+            }
+            other => panic!("expected a config error, got {other:?}"),
+        }
+
+        config.pii.dictionaries[0].path = Some(std::env::temp_dir().display().to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[cfg(feature = "pii")]
+    #[test]
+    fn test_invalid_allowlist() {
+        let mut config = GuardConfig::default();
+        config.pii.allow.cidrs.push("10.0.0.0/33".to_string());
+        match config.validate() {
+            Err(GuardError::ConfigError(message)) => assert!(message.contains("10.0.0.0/33")),
+            other => panic!("expected a config error, got {other:?}"),
+        }
+
+        config.pii.allow.cidrs.clear();
+        config.pii.allow.patterns.push("[unclosed".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let config = GuardConfig::default()
+            .with_overrides([
+                ("HANZO_GUARD_PII__DETECT_EMAIL", "false"),
+                ("HANZO_GUARD_CONTENT_FILTER__API_KEY", "secret"),
+                ("HANZO_GUARD_INJECTION__CUSTOM_PATTERNS", "foo, bar"),
+                ("HANZO_GUARD_RATE_LIMIT__BURST_SIZE", "3"),
+                ("HANZO_GUARD_CONFIG", "ignored.toml"),
+                ("PATH", "/usr/bin"),
+            ])
+            .unwrap();
+
+        assert!(!config.pii.detect_email);
+        assert_eq!(config.content_filter.api_key.as_deref(), Some("secret"));
+        assert_eq!(config.injection.custom_patterns, vec!["foo", "bar"]);
+        assert_eq!(config.rate_limit.burst_size, 3);
+    }
+
+    #[test]
+    fn test_stage_mode() {
+        let path = write_temp("toml", "[injection]\nmode = \"monitor\"\n");
+        let config = GuardConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.injection.mode, StageMode::Monitor);
+        assert_eq!(config.pii.mode, StageMode::Enforce);
+
+        let config = GuardConfig::default()
+            .with_overrides([("HANZO_GUARD_PII__MODE", "monitor")])
+            .unwrap();
+        assert_eq!(config.pii.mode, StageMode::Monitor);
+    }
+
+    #[test]
+    fn test_env_override_errors() {
+        let unknown = GuardConfig::default().with_overrides([("HANZO_GUARD_PII__NOPE", "1")]);
+        assert!(matches!(unknown, Err(GuardError::ConfigError(_))));
+
+        let bad_type =
+            GuardConfig::default().with_overrides([("HANZO_GUARD_PII__ENABLED", "maybe")]);
+        assert!(matches!(bad_type, Err(GuardError::ConfigError(_))));
+
+        let invalid = GuardConfig::default()
+            .with_overrides([("HANZO_GUARD_RATE_LIMIT__REQUESTS_PER_MINUTE", "0")]);
+        assert!(matches!(invalid, Err(GuardError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_per_type_strategies() {
+        let path = write_temp(
+            "toml",
+            "[pii]\npseudonym_secret = \"s3cret\"\n\n[pii.strategies]\nEmail = \"pseudonym\"\n",
+        );
+        let config = GuardConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            config.pii.strategy_for(&RedactionType::Email),
+            RedactionStrategy::Pseudonym
+        );
+        assert_eq!(
+            config.pii.strategy_for(&RedactionType::Ssn),
+            RedactionStrategy::Placeholder
+        );
+
+        // The pseudonym strategy needs a secret
+        let missing_secret = GuardConfig::default()
+            .with_overrides([("HANZO_GUARD_PII__STRATEGIES", r#"{"Phone":"pseudonym"}"#)]);
+        assert!(matches!(missing_secret, Err(GuardError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_custom_type_keys() {
+        let mut config = GuardConfig::default();
+        let codename = RedactionType::Custom("CODENAME".to_string());
+        config
+            .pii
+            .strategies
+            .insert(codename.clone(), RedactionStrategy::Drop);
+        config.pii.min_confidence.insert(codename.clone(), 0.9);
+
+        let config = config
+            .with_overrides([(
+                "HANZO_GUARD_PII__MIN_CONFIDENCE",
+                r#"{"CODENAME":0.7,"Ssn":0.8}"#,
+            )])
+            .unwrap();
+        assert_eq!(config.pii.strategy_for(&codename), RedactionStrategy::Drop);
+        assert_eq!(config.pii.min_confidence_for(&codename), 0.7);
+        assert_eq!(config.pii.min_confidence_for(&RedactionType::Ssn), 0.8);
+
+        let path = write_temp("toml", "[pii.strategies]\nCODENAME = \"mask\"\n");
+        let config = GuardConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.pii.strategy_for(&codename), RedactionStrategy::Mask);
+    }
+}
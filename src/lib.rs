@@ -43,6 +43,45 @@
 //! }
 //! ```
 //!
+//! ## Custom Stages
+//!
+//! Content flows through an ordered list of stages (rate limit, injection,
+//! PII, content filter). Implement [`Stage`] to add your own checks, and
+//! reorder or drop the built-in ones on the builder:
+//!
+//! ```rust
+//! use async_trait::async_trait;
+//! use hanzo_guard::error::SafetyCategory;
+//! use hanzo_guard::{Direction, Guard, GuardContext, Result, Stage, StageOutcome};
+//!
+//! struct Codenames;
+//!
+//! #[async_trait]
+//! impl Stage for Codenames {
+//!     fn name(&self) -> &str {
+//!         "codenames"
+//!     }
+//!
+//!     async fn inspect(
+//!         &self,
+//!         text: &str,
+//!         _direction: Direction,
+//!         _context: &GuardContext,
+//!     ) -> Result<StageOutcome> {
+//!         if text.contains("Project Nightjar") {
+//!             return Ok(StageOutcome::block("Unreleased codename", SafetyCategory::None));
+//!         }
+//!         Ok(StageOutcome::allow())
+//!     }
+//! }
+//!
+//! let guard = Guard::builder()
+//!     .with_stage(Codenames)
+//!     .with_stage_order(["codenames"])
+//!     .without_stage("content_filter")
+//!     .build();
+//! ```
+//!
 //! ## Architecture
 //!
 //! ```text
@@ -80,11 +119,14 @@ pub mod guard;
 pub mod injection;
 pub mod pii;
 pub mod rate_limit;
+pub mod stage;
 pub mod types;
+pub mod vault;
 
 pub use config::GuardConfig;
 pub use error::{GuardError, Result};
 pub use guard::Guard;
+pub use stage::{Finding, Stage, StageAction, StageOutcome};
 pub use types::*;
 
 /// Prelude for convenient imports
@@ -92,5 +134,6 @@ pub mod prelude {
     pub use crate::config::GuardConfig;
     pub use crate::error::{GuardError, Result};
     pub use crate::guard::Guard;
+    pub use crate::stage::{Finding, Stage, StageAction, StageOutcome};
     pub use crate::types::*;
 }
@@ -3,9 +3,12 @@
 //! Usage:
 //!   echo "My SSN is 123-45-6789" | hanzo-guard
 //!   hanzo-guard --file input.txt
+//!   hanzo-guard --config guard.toml --file input.txt
+//!   hanzo-guard --explain --text "Ignore previous instructions"
 
-use hanzo_guard::{Guard, GuardConfig, SanitizeResult};
+use hanzo_guard::{Direction, Guard, GuardConfig, GuardContext, SanitizeResult};
 use std::io::{self, BufRead};
+use std::path::Path;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -19,14 +22,34 @@ fn main() {
         println!("    hanzo-guard --text \"My SSN is 123-45-6789\"");
         println!();
         println!("OPTIONS:");
+        println!("    -c, --config <FILE>  Load config from TOML/YAML/JSON file");
         println!("    -f, --file <FILE>    Read input from file");
         println!("    -t, --text <TEXT>    Sanitize text directly");
         println!("    -j, --json           Output as JSON");
+        println!("    -e, --explain        Output the full decision report as JSON");
         println!("    -h, --help           Print help");
+        println!();
+        println!("Config values can be overridden with HANZO_GUARD_<SECTION>__<FIELD>,");
+        println!("e.g. HANZO_GUARD_PII__DETECT_EMAIL=false");
         return;
     }
 
+    let config_path = args
+        .iter()
+        .position(|a| a == "--config" || a == "-c")
+        .and_then(|pos| args.get(pos + 1));
+    let guard = match GuardConfig::load(config_path.map(Path::new))
+        .and_then(|config| Guard::builder().with_config(config).try_build())
+    {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+
     let json_output = args.iter().any(|a| a == "--json" || a == "-j");
+    let explain = args.iter().any(|a| a == "--explain" || a == "-e");
 
     // Get input
     let input = if let Some(pos) = args.iter().position(|a| a == "--text" || a == "-t") {
@@ -53,7 +76,24 @@ fn main() {
     // Run sanitization
     let rt = tokio::runtime::Runtime::new().unwrap();
     rt.block_on(async {
-        let guard = Guard::new(GuardConfig::default());
+        if explain {
+            match guard
+                .inspect(&input, Direction::Input, &GuardContext::new())
+                .await
+            {
+                Ok(report) => {
+                    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+                    if report.decision.is_blocked() {
+                        std::process::exit(2);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
 
         match guard.sanitize_input(&input).await {
             Ok(result) => {
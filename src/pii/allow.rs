@@ -0,0 +1,181 @@
+//! Allowlists that suppress PII findings
+//!
+//! Exact values are compared in a canonical form, so `4111 1111 1111 1111`
+//! also allows `4111-1111-1111-1111`. Patterns must match the whole value,
+//! domains cover their subdomains, and CIDR ranges apply to IP addresses.
+
+use super::ip::{in_range, parse_cidr};
+use crate::config::AllowConfig;
+use crate::error::{GuardError, Result};
+use crate::types::GuardContext;
+use regex::Regex;
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+/// Compiled allowlist
+pub(super) struct Allowlist {
+    values: HashSet<String>,
+    patterns: Vec<Regex>,
+    domains: Vec<String>,
+    cidrs: Vec<(IpAddr, u8)>,
+    metadata_keys: Vec<String>,
+}
+
+impl Allowlist {
+    /// Compile the patterns and parse the CIDR ranges of `config`
+    pub(super) fn compile(config: &AllowConfig) -> Result<Self> {
+        Ok(Self {
+            values: config.values.iter().map(|v| canonical(v)).collect(),
+            patterns: config
+                .patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(&format!("^(?:{pattern})$")).map_err(|e| {
+                        GuardError::ConfigError(format!(
+                            "invalid pattern in pii.allow: {pattern}: {e}"
+                        ))
+                    })
+                })
+                .collect::<Result<_>>()?,
+            domains: config
+                .domains
+                .iter()
+                .map(|domain| domain.trim_start_matches('@').to_lowercase())
+                .collect(),
+            cidrs: config
+                .cidrs
+                .iter()
+                .map(|cidr| {
+                    parse_cidr(cidr).ok_or_else(|| {
+                        GuardError::ConfigError(format!("invalid CIDR range in pii.allow: {cidr}"))
+                    })
+                })
+                .collect::<Result<_>>()?,
+            metadata_keys: config.metadata_keys.clone(),
+        })
+    }
+
+    /// Whether `value` is allowed for every request
+    pub(super) fn allows(&self, value: &str) -> bool {
+        self.values.contains(&canonical(value))
+            || self.patterns.iter().any(|p| p.is_match(value))
+            || self.allows_domain(value)
+            || self.allows_ip(value)
+    }
+
+    /// Whether `value` is one of the `context_values` of a request
+    pub(super) fn allows_for(&self, context_values: &HashSet<String>, value: &str) -> bool {
+        !context_values.is_empty() && context_values.contains(&canonical(value))
+    }
+
+    /// Values allowed for one request, taken from its metadata
+    pub(super) fn context_values(&self, context: &GuardContext) -> HashSet<String> {
+        let mut values = HashSet::new();
+        for key in &self.metadata_keys {
+            match context.metadata.get(key) {
+                Some(serde_json::Value::String(value)) => {
+                    values.insert(canonical(value));
+                }
+                Some(serde_json::Value::Array(items)) => {
+                    values.extend(items.iter().filter_map(|v| v.as_str()).map(canonical));
+                }
+                _ => {}
+            }
+        }
+        values
+    }
+
+    fn allows_domain(&self, value: &str) -> bool {
+        let Some((_, domain)) = value.rsplit_once('@') else {
+            return false;
+        };
+        let domain = domain.to_lowercase();
+        self.domains.iter().any(|allowed| {
+            domain == *allowed
+                || domain
+                    .strip_suffix(allowed.as_str())
+                    .is_some_and(|sub| sub.ends_with('.'))
+        })
+    }
+
+    fn allows_ip(&self, value: &str) -> bool {
+        let Some((ip, _)) = parse_cidr(value) else {
+            return false;
+        };
+        self.cidrs
+            .iter()
+            .any(|&(network, prefix)| in_range(ip, network, prefix))
+    }
+}
+
+/// Form in which values are compared
+///
+/// Numbers lose their spaces, dashes and parentheses; everything else is
+/// lowercased.
+fn canonical(value: &str) -> String {
+    let value = value.trim();
+    if value.chars().any(|c| c.is_ascii_digit())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ' ' | '-' | '(' | ')'))
+    {
+        value.chars().filter(char::is_ascii_digit).collect()
+    } else {
+        value.to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allow_rules() {
+        let allow = Allowlist::compile(&AllowConfig {
+            values: vec!["4111 1111 1111 1111".to_string()],
+            patterns: vec![r"test\d*@example\.org".to_string()],
+            domains: vec!["example.com".to_string()],
+            cidrs: vec!["127.0.0.0/8".to_string(), "fd00::/8".to_string()],
+            metadata_keys: vec![],
+        })
+        .unwrap();
+
+        assert!(allow.allows("4111-1111-1111-1111"));
+        assert!(allow.allows("test42@example.org"));
+        assert!(!allow.allows("xtest42@example.org"));
+        assert!(allow.allows("Jane@Mail.Example.com"));
+        assert!(!allow.allows("jane@notexample.com"));
+        assert!(allow.allows("127.0.0.1"));
+        assert!(allow.allows("fd12:3456::1"));
+        assert!(!allow.allows("128.0.0.1"));
+    }
+
+    #[test]
+    fn test_context_values() {
+        let allow = Allowlist::compile(&AllowConfig {
+            metadata_keys: vec!["email".to_string(), "phones".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        let context = GuardContext::new().with_metadata(serde_json::json!({
+            "email": "Jane@Acme.com",
+            "phones": ["415-867-5309"],
+            "ignored": "bob@acme.com",
+        }));
+
+        let values = allow.context_values(&context);
+        assert!(allow.allows_for(&values, "jane@acme.com"));
+        assert!(allow.allows_for(&values, "(415) 867-5309"));
+        assert!(!allow.allows_for(&values, "bob@acme.com"));
+        assert!(!allow.allows("jane@acme.com"));
+    }
+
+    #[test]
+    fn test_invalid_cidr_is_config_error() {
+        let result = Allowlist::compile(&AllowConfig {
+            cidrs: vec!["10.0.0.0/33".to_string()],
+            ..Default::default()
+        });
+        assert!(matches!(result, Err(GuardError::ConfigError(_))));
+    }
+}
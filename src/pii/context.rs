@@ -0,0 +1,181 @@
+//! Context keywords that raise or lower a finding's confidence
+//!
+//! Words just before or after a match say a lot about what it is: a nine-digit
+//! number after "SSN" is probably one, the same number after "order" is not.
+
+use super::phone;
+use crate::types::RedactionType;
+
+/// Bytes of text inspected before a match
+const WINDOW_BEFORE: usize = 40;
+/// Bytes of text inspected after a match
+const WINDOW_AFTER: usize = 20;
+/// Confidence added by a boosting keyword, or removed by a suppressing one
+const ADJUSTMENT: f32 = 0.35;
+
+const SSN_BOOST: &[&str] = &["ssn", "ssa", "social security", "soc sec"];
+const CARD_BOOST: &[&str] = &[
+    "amex",
+    "card",
+    "cc",
+    "credit",
+    "debit",
+    "mastercard",
+    "payment",
+    "visa",
+];
+const IP_BOOST: &[&str] = &["addr", "client", "host", "ip", "server", "src", "dst"];
+const IP_SUPPRESS: &[&str] = &["release", "v", "ver", "version"];
+const NAME_BOOST: &[&str] = &[
+    "customer",
+    "dear",
+    "employee",
+    "name",
+    "patient",
+    "regards",
+    "signed",
+    "sincerely",
+];
+const ADDRESS_BOOST: &[&str] = &[
+    "address",
+    "addr",
+    "deliver",
+    "lives",
+    "mailing",
+    "resides",
+    "residence",
+    "ship",
+];
+const API_KEY_BOOST: &[&str] = &["api", "auth", "bearer", "key", "secret", "token"];
+const WALLET_BOOST: &[&str] = &[
+    "address", "btc", "bitcoin", "deposit", "eth", "ethereum", "send", "wallet", "withdraw",
+];
+const MAC_BOOST: &[&str] = &["bssid", "ether", "hwaddr", "mac"];
+const IMEI_BOOST: &[&str] = &["device", "handset", "imei", "phone"];
+const DEVICE_ID_BOOST: &[&str] = &[
+    "aaid",
+    "adid",
+    "advertising",
+    "android",
+    "device",
+    "gaid",
+    "idfa",
+    "idfv",
+    "udid",
+];
+
+/// Keywords found around a match and the confidence change they imply
+pub(super) struct Context {
+    pub(super) adjustment: f32,
+    pub(super) keywords: Vec<String>,
+}
+
+/// Boosting and suppressing keywords for a type
+fn keywords(redaction_type: &RedactionType) -> (&'static [&'static str], &'static [&'static str]) {
+    match redaction_type {
+        RedactionType::Ssn => (SSN_BOOST, phone::REFERENCE_KEYWORDS),
+        RedactionType::CreditCard => (CARD_BOOST, phone::REFERENCE_KEYWORDS),
+        RedactionType::Phone => (phone::PHONE_KEYWORDS, phone::REFERENCE_KEYWORDS),
+        RedactionType::IpAddress => (IP_BOOST, IP_SUPPRESS),
+        RedactionType::ApiKey => (API_KEY_BOOST, &[]),
+        RedactionType::PersonName => (NAME_BOOST, &[]),
+        RedactionType::Address => (ADDRESS_BOOST, &[]),
+        RedactionType::CryptoWallet => (WALLET_BOOST, &[]),
+        RedactionType::MacAddress => (MAC_BOOST, &[]),
+        RedactionType::Imei => (IMEI_BOOST, &[]),
+        RedactionType::DeviceId => (DEVICE_ID_BOOST, &[]),
+        _ => (&[], &[]),
+    }
+}
+
+/// Look for context keywords around `text[start..end]`
+pub(super) fn score(
+    redaction_type: &RedactionType,
+    text: &str,
+    start: usize,
+    end: usize,
+) -> Context {
+    let (boost, suppress) = keywords(redaction_type);
+    let mut context = Context {
+        adjustment: 0.0,
+        keywords: vec![],
+    };
+    if boost.is_empty() && suppress.is_empty() {
+        return context;
+    }
+
+    let mut window_start = start.saturating_sub(WINDOW_BEFORE);
+    while !text.is_char_boundary(window_start) {
+        window_start += 1;
+    }
+    let mut window_end = (end + WINDOW_AFTER).min(text.len());
+    while !text.is_char_boundary(window_end) {
+        window_end -= 1;
+    }
+    let window =
+        format!("{} {}", &text[window_start..start], &text[end..window_end]).to_lowercase();
+    let words: Vec<&str> = window.split(|c: char| !c.is_alphanumeric()).collect();
+    let found = |keyword: &str| {
+        if keyword.contains(' ') {
+            window.contains(keyword)
+        } else {
+            words.contains(&keyword)
+        }
+    };
+
+    if let Some(keyword) = boost.iter().find(|k| found(k)) {
+        context.adjustment += ADJUSTMENT;
+        context.keywords.push(keyword.to_string());
+    }
+    if let Some(keyword) = suppress.iter().find(|k| found(k)) {
+        context.adjustment -= ADJUSTMENT;
+        context.keywords.push(keyword.to_string());
+    }
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score_of(redaction_type: &RedactionType, text: &str, value: &str) -> (f32, Vec<String>) {
+        let start = text.find(value).unwrap();
+        let context = score(redaction_type, text, start, start + value.len());
+        (context.adjustment, context.keywords)
+    }
+
+    #[test]
+    fn test_boost_and_suppress() {
+        assert_eq!(
+            score_of(
+                &RedactionType::Ssn,
+                "Social Security: 123456789",
+                "123456789"
+            ),
+            (ADJUSTMENT, vec!["social security".to_string()])
+        );
+        assert_eq!(
+            score_of(
+                &RedactionType::IpAddress,
+                "upgraded to version 1.2.3.4",
+                "1.2.3.4"
+            ),
+            (-ADJUSTMENT, vec!["version".to_string()])
+        );
+        // Keywords after the match count too
+        assert_eq!(
+            score_of(
+                &RedactionType::Phone,
+                "415-867-5309 (mobile)",
+                "415-867-5309"
+            )
+            .0,
+            ADJUSTMENT
+        );
+        // Whole words only: "tel" is not in "hotel"
+        assert_eq!(
+            score_of(&RedactionType::Phone, "hotel 415-867-5309", "415-867-5309").0,
+            0.0
+        );
+    }
+}
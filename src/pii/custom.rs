@@ -0,0 +1,157 @@
+//! User-supplied regex rules
+//!
+//! Rules from `PiiConfig::custom_rules` are compiled once, when the detector
+//! is built, so a bad pattern surfaces as a config error rather than at
+//! detection time. Like the built-in catalogs, a rule with capture groups
+//! redacts only the first group that matched.
+
+use super::rules::redacted_part;
+use crate::config::{CustomRuleConfig, RuleValidator};
+use crate::error::{GuardError, Result};
+use regex::Regex;
+
+/// A value found by a custom rule
+pub(super) struct CustomMatch<'a> {
+    pub(super) name: &'a str,
+    pub(super) start: usize,
+    pub(super) end: usize,
+}
+
+/// Compiled custom rules
+pub(super) struct CustomRules {
+    rules: Vec<(CustomRuleConfig, Regex)>,
+}
+
+impl CustomRules {
+    /// Compile every rule
+    pub(super) fn compile(configs: &[CustomRuleConfig]) -> Result<Self> {
+        let rules = configs
+            .iter()
+            .map(|config| {
+                let regex = Regex::new(&config.regex).map_err(|e| {
+                    GuardError::ConfigError(format!(
+                        "pii.custom_rules '{}' has an invalid regex: {e}",
+                        config.name
+                    ))
+                })?;
+                if regex.is_match("") {
+                    return Err(GuardError::ConfigError(format!(
+                        "pii.custom_rules '{}' matches an empty string",
+                        config.name
+                    )));
+                }
+                Ok((config.clone(), regex))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Patterns to register with the prefilter, in rule order
+    pub(super) fn patterns(&self) -> Vec<&str> {
+        self.rules
+            .iter()
+            .map(|(config, _)| config.regex.as_str())
+            .collect()
+    }
+
+    /// Find every match in `text`, in rule order
+    ///
+    /// `hits` says, for each rule, whether the prefilter saw its pattern.
+    pub(super) fn scan(&self, text: &str, hits: &[bool]) -> Vec<CustomMatch<'_>> {
+        let mut matches = vec![];
+        for ((config, regex), _) in self.rules.iter().zip(hits).filter(|(_, hit)| **hit) {
+            for caps in regex.captures_iter(text) {
+                let Some(m) = redacted_part(&caps).filter(|m| !m.is_empty()) else {
+                    continue;
+                };
+                if config
+                    .validator
+                    .is_some_and(|validator| !validate(validator, m.as_str()))
+                {
+                    continue;
+                }
+                matches.push(CustomMatch {
+                    name: &config.name,
+                    start: m.start(),
+                    end: m.end(),
+                });
+            }
+        }
+        matches
+    }
+
+    /// Placeholder configured for the rule called `name`
+    pub(super) fn replacement(&self, name: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|(config, _)| config.name == name)
+            .and_then(|(config, _)| config.replacement.as_deref())
+    }
+}
+
+fn validate(validator: RuleValidator, value: &str) -> bool {
+    match validator {
+        RuleValidator::Luhn => super::luhn_valid(value),
+        RuleValidator::Iban => super::locales::valid_iban(value),
+        RuleValidator::Verhoeff => super::locales::valid_verhoeff(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, regex: &str, validator: Option<RuleValidator>) -> CustomRuleConfig {
+        CustomRuleConfig {
+            name: name.to_string(),
+            regex: regex.to_string(),
+            validator,
+            replacement: None,
+        }
+    }
+
+    #[test]
+    fn test_capture_groups_and_validators() {
+        let rules = CustomRules::compile(&[
+            rule("EMPLOYEE_ID", r"\bemp[:#](E\d{6})\b", None),
+            rule("LOYALTY", r"\bL\d{11}\b", None),
+            rule("MEMBER", r"\bM-(\d{8})\b", Some(RuleValidator::Luhn)),
+        ])
+        .unwrap();
+
+        let text = "emp#E123456 M-79927398 M-79927390";
+        let found: Vec<_> = rules
+            .scan(text, &[true; 3])
+            .iter()
+            .map(|m| (m.name, &text[m.start..m.end]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("EMPLOYEE_ID", "E123456"), ("MEMBER", "79927398")]
+        );
+    }
+
+    #[test]
+    fn test_invalid_regex_is_config_error() {
+        let result = CustomRules::compile(&[rule("BROKEN", r"(unclosed", None)]);
+        match result {
+            Err(GuardError::ConfigError(message)) => assert!(message.contains("BROKEN")),
+            _ => panic!("expected a config error"),
+        }
+
+        assert!(CustomRules::compile(&[rule("DIGITS", r"\d*", None)]).is_err());
+    }
+
+    #[test]
+    fn test_empty_matches_are_skipped() {
+        // Never matches "" on its own, but matches nothing between words
+        let rules = CustomRules::compile(&[rule("DIGITS", r"\b\d*\b", None)]).unwrap();
+        let text = "ab 12";
+        let found: Vec<_> = rules
+            .scan(text, &[true])
+            .iter()
+            .map(|m| &text[m.start..m.end])
+            .collect();
+        assert_eq!(found, vec!["12"]);
+    }
+}
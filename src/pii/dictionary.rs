@@ -0,0 +1,203 @@
+//! Customer-defined DLP dictionaries
+//!
+//! Every term from every dictionary goes into one Aho-Corasick automaton, so
+//! matching costs the same for ten terms or fifty thousand. Terms and text
+//! are both lowercased with whitespace runs collapsed, and a term only
+//! matches as a whole word.
+
+use crate::config::{DictionaryAction, DictionaryConfig};
+use crate::error::{GuardError, Result};
+use aho_corasick::{AhoCorasick, MatchKind};
+
+/// A dictionary term found in text
+pub(super) struct DictionaryMatch<'a> {
+    pub(super) dictionary: &'a str,
+    pub(super) start: usize,
+    pub(super) end: usize,
+}
+
+/// Compiled dictionaries
+pub(super) struct DictionaryMatcher {
+    automaton: Option<AhoCorasick>,
+    /// Index into `dictionaries` for each pattern
+    owners: Vec<usize>,
+    dictionaries: Vec<(String, DictionaryAction)>,
+}
+
+impl DictionaryMatcher {
+    /// Read every dictionary's word list and compile them together
+    pub(super) fn load(configs: &[DictionaryConfig]) -> Result<Self> {
+        let mut patterns = vec![];
+        let mut owners = vec![];
+        for (index, config) in configs.iter().enumerate() {
+            let mut terms = config.terms.clone();
+            if let Some(path) = &config.path {
+                let contents = std::fs::read_to_string(path).map_err(|e| {
+                    GuardError::ConfigError(format!(
+                        "failed to read dictionary '{}' from {path}: {e}",
+                        config.name
+                    ))
+                })?;
+                terms.extend(
+                    contents
+                        .lines()
+                        .filter(|line| !line.trim_start().starts_with('#'))
+                        .map(String::from),
+                );
+            }
+            for term in terms {
+                let (normalized, _) = normalize(term.trim());
+                if !normalized.is_empty() {
+                    patterns.push(normalized);
+                    owners.push(index);
+                }
+            }
+        }
+
+        let automaton = if patterns.is_empty() {
+            None
+        } else {
+            let automaton = AhoCorasick::builder()
+                .match_kind(MatchKind::LeftmostLongest)
+                .build(&patterns)
+                .map_err(|e| {
+                    GuardError::ConfigError(format!("failed to compile dictionaries: {e}"))
+                })?;
+            Some(automaton)
+        };
+
+        Ok(Self {
+            automaton,
+            owners,
+            dictionaries: configs.iter().map(|c| (c.name.clone(), c.action)).collect(),
+        })
+    }
+
+    /// Find whole-word occurrences of any term
+    pub(super) fn scan(&self, text: &str) -> Vec<DictionaryMatch<'_>> {
+        let Some(automaton) = &self.automaton else {
+            return vec![];
+        };
+
+        let (normalized, offsets) = normalize(text);
+        automaton
+            .find_iter(&normalized)
+            .filter(|m| is_whole_word(&normalized, m.start(), m.end()))
+            .map(|m| DictionaryMatch {
+                dictionary: &self.dictionaries[self.owners[m.pattern().as_usize()]].0,
+                start: offsets[m.start()],
+                end: offsets[m.end()],
+            })
+            .collect()
+    }
+
+    /// Whether matches from the named dictionary block the content
+    pub(super) fn blocks(&self, dictionary: &str) -> bool {
+        self.dictionaries
+            .iter()
+            .any(|(name, action)| name == dictionary && *action == DictionaryAction::Block)
+    }
+}
+
+/// Lowercase `text` and collapse whitespace runs to one space
+///
+/// Also returns, for every byte of the result plus its end, the matching
+/// byte offset in `text`.
+fn normalize(text: &str) -> (String, Vec<usize>) {
+    let mut normalized = String::with_capacity(text.len());
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    let mut in_whitespace = false;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            if !in_whitespace {
+                normalized.push(' ');
+                offsets.push(i);
+            }
+            in_whitespace = true;
+            continue;
+        }
+        in_whitespace = false;
+        for lower in c.to_lowercase() {
+            let before = normalized.len();
+            normalized.push(lower);
+            offsets.extend(std::iter::repeat_n(i, normalized.len() - before));
+        }
+    }
+    offsets.push(text.len());
+    (normalized, offsets)
+}
+
+/// Whether a match does not continue into letters or digits on either side
+fn is_whole_word(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    let first = text[start..end].chars().next();
+    let last = text[start..end].chars().next_back();
+    let joins = |outer: Option<char>, inner: Option<char>| {
+        outer.is_some_and(char::is_alphanumeric) && inner.is_some_and(char::is_alphanumeric)
+    };
+    !joins(before, first) && !joins(after, last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary(name: &str, terms: &[&str], action: DictionaryAction) -> DictionaryConfig {
+        DictionaryConfig {
+            name: name.to_string(),
+            path: None,
+            terms: terms.iter().map(|t| t.to_string()).collect(),
+            action,
+        }
+    }
+
+    #[test]
+    fn test_normalized_whole_word_matching() {
+        let matcher = DictionaryMatcher::load(&[dictionary(
+            "CODENAME",
+            &["Nightjar", "project  blue   heron"],
+            DictionaryAction::Redact,
+        )])
+        .unwrap();
+
+        let text = "Ship NIGHTJAR and Project\n Blue Heron, not Nightjars.";
+        let found: Vec<_> = matcher
+            .scan(text)
+            .iter()
+            .map(|m| (m.dictionary, &text[m.start..m.end]))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("CODENAME", "NIGHTJAR"),
+                ("CODENAME", "Project\n Blue Heron")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_word_list_file() {
+        let path = std::env::temp_dir().join(format!("hanzo-guard-{}.txt", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "# customers\nAcme Corp\n\nGlobex\n").unwrap();
+        let config = DictionaryConfig {
+            path: Some(path.display().to_string()),
+            ..dictionary("CUSTOMER", &[], DictionaryAction::Block)
+        };
+        let matcher = DictionaryMatcher::load(&[config]).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(matcher.scan("globex and acme corp").len(), 2);
+        assert!(matcher.scan("# customers").is_empty());
+        assert!(matcher.blocks("CUSTOMER"));
+
+        let missing = DictionaryConfig {
+            path: Some("/nonexistent/terms.txt".to_string()),
+            ..dictionary("CUSTOMER", &[], DictionaryAction::Redact)
+        };
+        assert!(matches!(
+            DictionaryMatcher::load(&[missing]),
+            Err(GuardError::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,306 @@
+//! Opt-in wallet address and device identifier packs
+//!
+//! Bitcoin addresses are checked against their base58check or
+//! bech32/bech32m checksum, mixed-case Ethereum addresses against EIP-55,
+//! and IMEIs against their Luhn digit. Identifiers with no checksum (MAC
+//! addresses, UUID device IDs, unchecksummed Ethereum addresses, bare
+//! 15-digit IMEIs) start below the confidence threshold and are only
+//! flagged next to a keyword, so plain hex strings and request IDs stay.
+
+use super::luhn_valid;
+use super::rules::Rule;
+use crate::config::PiiConfig;
+use crate::types::RedactionType;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+const CRYPTO_WALLETS: &[Rule] = &[
+    Rule {
+        id: "bitcoin_address",
+        redaction_type: RedactionType::CryptoWallet,
+        pattern: r"\b[13][1-9A-HJ-NP-Za-km-z]{25,34}\b",
+        validate: Some(valid_base58check),
+        confidence: 0.95,
+    },
+    Rule {
+        id: "bitcoin_bech32_address",
+        redaction_type: RedactionType::CryptoWallet,
+        pattern: r"(?i)\bbc1[02-9ac-hj-np-z]{11,71}\b",
+        validate: Some(valid_bech32),
+        confidence: 0.95,
+    },
+    Rule {
+        id: "ethereum_address",
+        redaction_type: RedactionType::CryptoWallet,
+        pattern: r"\b0x[0-9a-fA-F]{40}\b",
+        validate: Some(valid_eip55),
+        confidence: 0.95,
+    },
+    Rule {
+        id: "ethereum_address_unchecksummed",
+        redaction_type: RedactionType::CryptoWallet,
+        pattern: r"\b0x[0-9a-fA-F]{40}\b",
+        validate: Some(unchecksummed),
+        confidence: 0.4,
+    },
+];
+
+const MAC_ADDRESSES: &[Rule] = &[Rule {
+    id: "mac_address",
+    redaction_type: RedactionType::MacAddress,
+    // Not part of a longer colon- or dash-separated run, such as an IPv6 address
+    pattern: r"(?:^|[^:\w-])((?:[0-9A-Fa-f]{2}:){5}[0-9A-Fa-f]{2}|(?:[0-9A-Fa-f]{2}-){5}[0-9A-Fa-f]{2})(?:$|[^:\w-])",
+    validate: Some(valid_mac),
+    confidence: 0.4,
+}];
+
+const IMEIS: &[Rule] = &[
+    Rule {
+        id: "imei",
+        redaction_type: RedactionType::Imei,
+        pattern: r"\b\d{2}[- ]\d{6}[- ]\d{6}[- ]\d\b",
+        validate: Some(luhn_valid),
+        confidence: 0.8,
+    },
+    Rule {
+        id: "imei_unformatted",
+        redaction_type: RedactionType::Imei,
+        pattern: r"\b\d{15}\b",
+        validate: Some(luhn_valid),
+        confidence: 0.4,
+    },
+];
+
+const DEVICE_IDS: &[Rule] = &[Rule {
+    id: "device_uuid",
+    redaction_type: RedactionType::DeviceId,
+    pattern: r"\b[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}\b",
+    validate: Some(valid_uuid),
+    confidence: 0.3,
+}];
+
+/// Rules for the enabled identifier types
+pub(super) fn rules(config: &PiiConfig) -> Vec<&'static Rule> {
+    [
+        (config.detect_crypto_wallets, CRYPTO_WALLETS),
+        (config.detect_mac_addresses, MAC_ADDRESSES),
+        (config.detect_imei, IMEIS),
+        (config.detect_device_ids, DEVICE_IDS),
+    ]
+    .into_iter()
+    .filter(|(enabled, _)| *enabled)
+    .flat_map(|(_, rules)| rules)
+    .collect()
+}
+
+/// Legacy (P2PKH) and script (P2SH) Bitcoin addresses
+fn valid_base58check(value: &str) -> bool {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    // Big-endian base-256 digits of the decoded number
+    let mut bytes: Vec<u8> = vec![];
+    for c in value.bytes() {
+        let Some(mut carry) = ALPHABET.iter().position(|&a| a == c).map(|d| d as u32) else {
+            return false;
+        };
+        for byte in bytes.iter_mut().rev() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+    // Leading '1's stand for zero bytes
+    let zeros = value.bytes().take_while(|&c| c == b'1').count();
+    let mut decoded = vec![0; zeros];
+    decoded.extend(bytes);
+
+    if decoded.len() != 25 || !matches!(decoded[0], 0x00 | 0x05) {
+        return false;
+    }
+    let (payload, checksum) = decoded.split_at(21);
+    Sha256::digest(Sha256::digest(payload))[..4] == *checksum
+}
+
+/// SegWit addresses: bech32 for witness version 0, bech32m (BIP 350) above
+fn valid_bech32(value: &str) -> bool {
+    const CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+    const BECH32: u32 = 1;
+    const BECH32M: u32 = 0x2bc8_30a3;
+
+    if !single_case(value) {
+        return false;
+    }
+    let value = value.to_ascii_lowercase();
+    let (hrp, data) = value.split_at(value.rfind('1').unwrap());
+    let data: Option<Vec<u32>> = data[1..]
+        .bytes()
+        .map(|c| CHARSET.iter().position(|&a| a == c).map(|d| d as u32))
+        .collect();
+    let Some(data) = data else {
+        return false;
+    };
+
+    let hrp = hrp.bytes().map(u32::from);
+    let values = hrp
+        .clone()
+        .map(|c| c >> 5)
+        .chain([0])
+        .chain(hrp.map(|c| c & 31))
+        .chain(data.iter().copied());
+    let expected = if data[0] == 0 { BECH32 } else { BECH32M };
+    bech32_polymod(values) == expected
+}
+
+fn bech32_polymod(values: impl Iterator<Item = u32>) -> u32 {
+    const GENERATOR: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    let mut checksum = 1u32;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ff_ffff) << 5) ^ value;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+/// Mixed-case Ethereum address whose capitals match its EIP-55 checksum
+fn valid_eip55(value: &str) -> bool {
+    let hex = &value[2..];
+    if single_case(hex) {
+        return false;
+    }
+    let hash = Keccak256::digest(hex.to_ascii_lowercase().as_bytes());
+    hex.chars().enumerate().all(|(i, c)| {
+        let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0xf;
+        !c.is_ascii_alphabetic() || c.is_ascii_uppercase() == (nibble >= 8)
+    })
+}
+
+/// All-lowercase or all-uppercase Ethereum address, which carries no checksum
+fn unchecksummed(value: &str) -> bool {
+    single_case(&value[2..])
+}
+
+/// Whether `value` has no lowercase or no uppercase letters
+fn single_case(value: &str) -> bool {
+    !value.bytes().any(|c| c.is_ascii_lowercase()) || !value.bytes().any(|c| c.is_ascii_uppercase())
+}
+
+/// Anything but the all-zero and broadcast addresses
+fn valid_mac(value: &str) -> bool {
+    let digits: String = value.chars().filter(char::is_ascii_hexdigit).collect();
+    !digits.chars().all(|c| c == '0') && !digits.chars().all(|c| c.eq_ignore_ascii_case(&'f'))
+}
+
+/// RFC 9562 UUID with a known version and variant, which rules out the nil
+/// and max UUIDs
+fn valid_uuid(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    matches!(bytes[14], b'1'..=b'8')
+        && matches!(bytes[19].to_ascii_lowercase(), b'8' | b'9' | b'a' | b'b')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rules::RuleScanner;
+    use super::*;
+
+    fn found(config: &PiiConfig, text: &str) -> Vec<(&'static str, String)> {
+        let scanner = RuleScanner::new(rules(config)).unwrap();
+        scanner
+            .scan(text, &vec![true; scanner.patterns().len()])
+            .iter()
+            .map(|m| (m.rule, text[m.start..m.end].to_string()))
+            .collect()
+    }
+
+    fn all() -> PiiConfig {
+        PiiConfig {
+            detect_crypto_wallets: true,
+            detect_mac_addresses: true,
+            detect_imei: true,
+            detect_device_ids: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_valid_identifiers() {
+        let cases = [
+            ("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", "bitcoin_address"),
+            ("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy", "bitcoin_address"),
+            (
+                "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq",
+                "bitcoin_bech32_address",
+            ),
+            (
+                "bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297",
+                "bitcoin_bech32_address",
+            ),
+            (
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+                "ethereum_address",
+            ),
+            (
+                "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+                "ethereum_address",
+            ),
+            (
+                "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+                "ethereum_address_unchecksummed",
+            ),
+            ("00:1A:2B:3C:4D:5E", "mac_address"),
+            ("00-1a-2b-3c-4d-5e", "mac_address"),
+            ("49-015420-323751-8", "imei"),
+            ("490154203237518", "imei_unformatted"),
+            ("3f2504e0-4f89-41d3-9a0c-0305e82c3301", "device_uuid"),
+        ];
+        for (value, rule) in cases {
+            assert_eq!(
+                found(&all(), &format!("id: {value}.")),
+                vec![(rule, value.to_string())],
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_checksums_reject_lookalikes() {
+        for value in [
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb",
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdr",
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5MDQ",
+            // EIP-55 with one letter's case flipped
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD",
+            "ff:ff:ff:ff:ff:ff",
+            "2001:db8:0:aa:bb:cc:dd:ee",
+            "49-015420-323751-9",
+            "00000000-0000-0000-0000-000000000000",
+        ] {
+            assert!(found(&all(), value).is_empty(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_packs_are_opt_in() {
+        assert!(rules(&PiiConfig::default()).is_empty());
+        let config = PiiConfig {
+            detect_imei: true,
+            ..Default::default()
+        };
+        let ids: Vec<_> = rules(&config).iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec!["imei", "imei_unformatted"]);
+    }
+}
@@ -0,0 +1,128 @@
+//! IP address validation and reserved ranges
+//!
+//! The detection regexes only find address-shaped tokens. Each token is
+//! parsed with the standard library, so `999.1.1.1`, timestamps, MAC
+//! addresses and Rust paths like `fmt::Debug` are dropped, while compressed
+//! IPv6 (`::1`, `fe80::1%eth0`), IPv4-mapped addresses and CIDR suffixes are
+//! understood. Ports and zone IDs are left outside the match.
+
+use crate::config::IpRange;
+use regex::Match;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+const PRIVATE: &[(IpAddr, u8)] = &[
+    (v4(10, 0, 0, 0), 8),
+    (v4(172, 16, 0, 0), 12),
+    (v4(192, 168, 0, 0), 16),
+    (v4(100, 64, 0, 0), 10),
+    (v6(0xfc00, 0), 7),
+];
+const LOOPBACK: &[(IpAddr, u8)] = &[
+    (v4(127, 0, 0, 0), 8),
+    (IpAddr::V6(Ipv6Addr::LOCALHOST), 128),
+];
+const LINK_LOCAL: &[(IpAddr, u8)] = &[(v4(169, 254, 0, 0), 16), (v6(0xfe80, 0), 10)];
+const DOCUMENTATION: &[(IpAddr, u8)] = &[
+    (v4(192, 0, 2, 0), 24),
+    (v4(198, 51, 100, 0), 24),
+    (v4(203, 0, 113, 0), 24),
+    (v6(0x2001, 0xdb8), 32),
+];
+
+const fn v4(a: u8, b: u8, c: u8, d: u8) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(a, b, c, d))
+}
+
+const fn v6(a: u16, b: u16) -> IpAddr {
+    IpAddr::V6(Ipv6Addr::new(a, b, 0, 0, 0, 0, 0, 0))
+}
+
+/// The address of an IP-shaped match, if it is a whole token that parses
+pub(super) fn parse(text: &str, m: Match) -> Option<IpAddr> {
+    let value = m.as_str();
+    let v6 = value.contains(':');
+    let before = text[..m.start()].chars().next_back();
+    let mut after = text[m.end()..].chars();
+    let next = after.next();
+    // Not part of a version string, hostname or longer hex run; `ip:` may
+    // precede an IPv4 address and a port may follow it
+    if before.is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '.') || c == ':' && v6)
+        || next.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == ':' && v6)
+        || next == Some('.') && after.next().is_some_and(char::is_alphanumeric)
+    {
+        return None;
+    }
+    // Rust and C++ paths such as `a::b` parse as IPv6 too
+    if v6 && !value.contains(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    parse_cidr(value).map(|(address, _)| address)
+}
+
+/// Whether `ip` lies in one of `ranges`
+pub(super) fn in_ranges(ip: IpAddr, ranges: &[IpRange]) -> bool {
+    let ip = ip.to_canonical();
+    ranges.iter().any(|range| {
+        let networks = match range {
+            IpRange::Private => PRIVATE,
+            IpRange::Loopback => LOOPBACK,
+            IpRange::LinkLocal => LINK_LOCAL,
+            IpRange::Documentation => DOCUMENTATION,
+        };
+        networks
+            .iter()
+            .any(|&(network, prefix)| in_range(ip, network, prefix))
+    })
+}
+
+/// Parse `10.0.0.0/8` or `fd00::/8`; a bare address is a single-host range
+pub(super) fn parse_cidr(cidr: &str) -> Option<(IpAddr, u8)> {
+    let (address, prefix) = match cidr.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (cidr, None),
+    };
+    let address: IpAddr = address.trim().parse().ok()?;
+    let max = if address.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(prefix) => prefix.trim().parse().ok().filter(|&p| p <= max)?,
+        None => max,
+    };
+    Some((address, prefix))
+}
+
+/// Whether `ip` is in `network/prefix`
+pub(super) fn in_range(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserved_ranges() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        assert!(in_ranges(ip("10.1.2.3"), &[IpRange::Private]));
+        assert!(in_ranges(ip("fd12::1"), &[IpRange::Private]));
+        assert!(in_ranges(ip("::ffff:192.168.0.9"), &[IpRange::Private]));
+        assert!(in_ranges(ip("::1"), &[IpRange::Loopback]));
+        assert!(in_ranges(ip("fe80::1"), &[IpRange::LinkLocal]));
+        assert!(in_ranges(ip("2001:db8::7"), &[IpRange::Documentation]));
+        assert!(in_ranges(ip("203.0.113.5"), &[IpRange::Documentation]));
+        assert!(!in_ranges(
+            ip("8.8.8.8"),
+            &[IpRange::Private, IpRange::Loopback]
+        ));
+        assert!(!in_ranges(ip("10.1.2.3"), &[IpRange::Loopback]));
+    }
+}
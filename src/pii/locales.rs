@@ -0,0 +1,354 @@
+//! Locale-specific PII packs
+//!
+//! Every identifier with a published check digit is validated (IBAN mod-97,
+//! SIN Luhn, Aadhaar Verhoeff, PESEL/BSN/NIR/DNI checksums), and NI numbers
+//! are checked against the prefixes HMRC never issues. The BSN checksum
+//! passes one random number in eleven, so BSNs are only flagged next to a
+//! keyword.
+
+use super::luhn_valid;
+use super::rules::Rule;
+use crate::config::Locale;
+use crate::types::RedactionType;
+
+/// IBANs, shared by the EU and GB packs
+const IBAN: &[Rule] = &[Rule {
+    id: "iban",
+    redaction_type: RedactionType::Iban,
+    pattern: r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b",
+    validate: Some(valid_iban),
+    confidence: 0.95,
+}];
+
+const EU: &[Rule] = &[
+    Rule {
+        id: "es_dni",
+        redaction_type: RedactionType::EuNationalId,
+        pattern: r"\b(?:\d{8}|[XYZ]-?\d{7})-?[A-Z]\b",
+        validate: Some(valid_dni),
+        confidence: 0.9,
+    },
+    Rule {
+        id: "nl_bsn",
+        redaction_type: RedactionType::EuNationalId,
+        pattern: r"(?i)\b(?:bsn|burgerservicenummer|sofinummer)\b\D{0,20}?\b(\d{9}|\d{4}\.\d{2}\.\d{3})\b",
+        validate: Some(valid_bsn),
+        confidence: 0.9,
+    },
+    Rule {
+        id: "pl_pesel",
+        redaction_type: RedactionType::EuNationalId,
+        pattern: r"\b\d{11}\b",
+        validate: Some(valid_pesel),
+        confidence: 0.8,
+    },
+    Rule {
+        id: "fr_nir",
+        redaction_type: RedactionType::EuNationalId,
+        pattern: r"\b[12] ?\d{2} ?(?:0[1-9]|1[0-2]|[2-9]\d) ?(?:\d{2}|2[AB]) ?\d{3} ?\d{3} ?\d{2}\b",
+        validate: Some(valid_nir),
+        confidence: 0.9,
+    },
+];
+
+const GB: &[Rule] = &[Rule {
+    id: "uk_nino",
+    redaction_type: RedactionType::UkNino,
+    pattern: r"\b[A-CEGHJ-PR-TW-Z][A-CEGHJ-NPR-TW-Z] ?\d{2} ?\d{2} ?\d{2} ?[A-D]\b",
+    validate: Some(valid_nino),
+    confidence: 0.85,
+}];
+
+const CA: &[Rule] = &[Rule {
+    id: "ca_sin",
+    redaction_type: RedactionType::CaSin,
+    pattern: r"\b[1-79]\d{2}[- ]?\d{3}[- ]?\d{3}\b",
+    validate: Some(luhn_valid),
+    confidence: 0.7,
+}];
+
+const IN: &[Rule] = &[
+    Rule {
+        id: "in_aadhaar",
+        redaction_type: RedactionType::InAadhaar,
+        pattern: r"\b[2-9]\d{3}[ -]?\d{4}[ -]?\d{4}\b",
+        validate: Some(valid_verhoeff),
+        confidence: 0.85,
+    },
+    Rule {
+        id: "in_pan",
+        redaction_type: RedactionType::InPan,
+        // Fourth letter is the holder type (P = person, C = company, ...)
+        pattern: r"\b[A-Z]{3}[ABCFGHJLPT][A-Z]\d{4}[A-Z]\b",
+        validate: None,
+        confidence: 0.75,
+    },
+];
+
+/// Rules for the selected locales, each included once
+pub(super) fn rules(locales: &[Locale]) -> Vec<&'static Rule> {
+    let mut packs: Vec<&'static [Rule]> = vec![];
+    if locales.contains(&Locale::Eu) || locales.contains(&Locale::Gb) {
+        packs.push(IBAN);
+    }
+    for (locale, pack) in [
+        (Locale::Eu, EU),
+        (Locale::Gb, GB),
+        (Locale::Ca, CA),
+        (Locale::In, IN),
+    ] {
+        if locales.contains(&locale) {
+            packs.push(pack);
+        }
+    }
+    packs.into_iter().flatten().collect()
+}
+
+fn digits(value: &str) -> Vec<u32> {
+    value.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+/// Registered IBAN lengths for common countries
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24),
+    ("AT", 20),
+    ("BE", 16),
+    ("BG", 22),
+    ("CH", 21),
+    ("CY", 28),
+    ("CZ", 24),
+    ("DE", 22),
+    ("DK", 18),
+    ("EE", 20),
+    ("ES", 24),
+    ("FI", 18),
+    ("FR", 27),
+    ("GB", 22),
+    ("GR", 27),
+    ("HR", 21),
+    ("HU", 28),
+    ("IE", 22),
+    ("IS", 26),
+    ("IT", 27),
+    ("LI", 21),
+    ("LT", 20),
+    ("LU", 20),
+    ("LV", 21),
+    ("MC", 27),
+    ("MT", 31),
+    ("NL", 18),
+    ("NO", 15),
+    ("PL", 28),
+    ("PT", 25),
+    ("RO", 24),
+    ("SE", 24),
+    ("SI", 19),
+    ("SK", 24),
+];
+
+/// Country length and ISO 13616 mod-97 check
+pub(super) fn valid_iban(value: &str) -> bool {
+    let iban: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    // Custom rules can hand over any text, and the split below is by bytes
+    if !iban.is_ascii() || iban.len() < 4 {
+        return false;
+    }
+    let expected = IBAN_LENGTHS
+        .iter()
+        .find(|(country, _)| iban.starts_with(country))
+        .map(|(_, len)| *len);
+    match expected {
+        Some(len) if iban.len() != len => return false,
+        None if !(15..=34).contains(&iban.len()) => return false,
+        _ => {}
+    }
+
+    let (head, tail) = iban.split_at(4);
+    let mut remainder = 0u32;
+    for c in tail.chars().chain(head.chars()) {
+        let Some(n) = c.to_digit(36) else {
+            return false;
+        };
+        remainder = if n < 10 {
+            (remainder * 10 + n) % 97
+        } else {
+            (remainder * 100 + n) % 97
+        };
+    }
+    remainder == 1
+}
+
+/// Spanish DNI/NIE control letter
+fn valid_dni(value: &str) -> bool {
+    const LETTERS: &[u8] = b"TRWAGMYFPDXBNJZSQVHLCKE";
+    let value = value.replace('-', "");
+    let (body, letter) = value.split_at(value.len() - 1);
+    let number = match body.as_bytes().first() {
+        Some(b'X') => format!("0{}", &body[1..]),
+        Some(b'Y') => format!("1{}", &body[1..]),
+        Some(b'Z') => format!("2{}", &body[1..]),
+        _ => body.to_string(),
+    };
+    number
+        .parse::<usize>()
+        .is_ok_and(|n| letter.as_bytes()[0] == LETTERS[n % 23])
+}
+
+/// Dutch BSN eleven-test
+fn valid_bsn(value: &str) -> bool {
+    let d = digits(value);
+    if d.len() != 9 {
+        return false;
+    }
+    let sum: i64 = d[..8]
+        .iter()
+        .zip((2..=9).rev())
+        .map(|(&digit, weight)| i64::from(digit) * weight)
+        .sum::<i64>()
+        - i64::from(d[8]);
+    sum != 0 && sum % 11 == 0
+}
+
+/// Polish PESEL checksum and encoded birth date
+fn valid_pesel(value: &str) -> bool {
+    let d = digits(value);
+    if d.len() != 11 {
+        return false;
+    }
+    const WEIGHTS: [u32; 10] = [1, 3, 7, 9, 1, 3, 7, 9, 1, 3];
+    let sum: u32 = d.iter().zip(WEIGHTS).map(|(&digit, w)| digit * w).sum();
+    let month = d[2] * 10 + d[3];
+    let day = d[4] * 10 + d[5];
+    // Months are offset by 20 per century: 01-12, 21-32, 41-52, 61-72, 81-92
+    (1..=12).contains(&(month % 20)) && (1..=31).contains(&day) && (10 - sum % 10) % 10 == d[10]
+}
+
+/// French NIR (social security number) key
+fn valid_nir(value: &str) -> bool {
+    let compact: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let (body, key) = compact.split_at(13);
+    // Corsican departments 2A and 2B count as 19 and 18
+    let number = body.replace("2A", "19").replace("2B", "18");
+    match (number.parse::<u64>(), key.parse::<u64>()) {
+        (Ok(n), Ok(key)) => 97 - n % 97 == key,
+        _ => false,
+    }
+}
+
+/// Prefixes HMRC never allocates
+const INVALID_NINO_PREFIXES: &[&str] = &["BG", "GB", "KN", "NK", "NT", "TN", "ZZ"];
+
+fn valid_nino(value: &str) -> bool {
+    !INVALID_NINO_PREFIXES.contains(&&value[..2])
+}
+
+/// Verhoeff check digit (Aadhaar)
+pub(super) fn valid_verhoeff(value: &str) -> bool {
+    const D: [[usize; 10]; 10] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+        [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+        [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+        [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+        [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+        [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+        [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+        [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+        [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+    ];
+    const P: [[usize; 10]; 8] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+        [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+        [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+        [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+        [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+        [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+        [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+    ];
+
+    let check = digits(value)
+        .iter()
+        .rev()
+        .enumerate()
+        .fold(0, |c, (i, &digit)| D[c][P[i % 8][digit as usize]]);
+    check == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rules::RuleScanner;
+    use super::*;
+
+    fn found(locales: &[Locale], text: &str) -> Vec<(&'static str, String)> {
+        let scanner = RuleScanner::new(rules(locales)).unwrap();
+        scanner
+            .scan(text, &vec![true; scanner.patterns().len()])
+            .iter()
+            .map(|m| (m.rule, text[m.start..m.end].to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_identifiers() {
+        let cases = [
+            (Locale::Eu, "DE89 3704 0044 0532 0130 00", "iban"),
+            (Locale::Gb, "GB82WEST12345698765432", "iban"),
+            (Locale::Gb, "AB 12 34 56 C", "uk_nino"),
+            (Locale::Ca, "130 692 544", "ca_sin"),
+            (Locale::In, "2345 6789 0124", "in_aadhaar"),
+            (Locale::In, "ABCPE1234F", "in_pan"),
+            (Locale::Eu, "12345678Z", "es_dni"),
+            (Locale::Eu, "X1234567L", "es_dni"),
+            (Locale::Eu, "44051401359", "pl_pesel"),
+            (Locale::Eu, "1 84 12 76 451 089 46", "fr_nir"),
+        ];
+        for (locale, value, rule) in cases {
+            assert_eq!(
+                found(&[locale], &format!("id: {value}.")),
+                vec![(rule, value.to_string())],
+                "{value}"
+            );
+        }
+
+        assert_eq!(
+            found(&[Locale::Eu], "Mijn BSN is 1112.22.333"),
+            vec![("nl_bsn", "1112.22.333".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_checksums_reject_lookalikes() {
+        for (locale, value) in [
+            (Locale::Eu, "DE89 3704 0044 0532 0130 01"),
+            (Locale::Gb, "GB 12 34 56 C"),
+            (Locale::Ca, "130 692 545"),
+            (Locale::In, "2345 6789 0125"),
+            (Locale::Eu, "12345678A"),
+            (Locale::Eu, "44051401358"),
+            (Locale::Eu, "1 84 12 76 451 089 47"),
+            // Valid BSN without a keyword
+            (Locale::Eu, "order 111222333"),
+        ] {
+            assert!(found(&[locale], value).is_empty(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_packs_are_opt_in() {
+        assert!(rules(&[]).is_empty());
+        let ids: Vec<_> = rules(&[Locale::Eu, Locale::Gb])
+            .iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids.iter().filter(|id| **id == "iban").count(), 1);
+    }
+
+    #[test]
+    fn test_iban_rejects_non_ascii() {
+        assert!(valid_iban("GB82WEST12345698765432"));
+        assert!(!valid_iban("XXXé123456789012"));
+        assert!(!valid_iban("ü"));
+        assert!(!valid_iban(""));
+    }
+}
@@ -0,0 +1,128 @@
+//! Masking for the mask and partial-mask strategies
+
+use crate::types::RedactionType;
+
+/// Character used in place of masked characters
+const MASK: char = '*';
+
+/// Mask every letter and digit, keeping separators
+pub(crate) fn mask(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_alphanumeric() { MASK } else { c })
+        .collect()
+}
+
+/// Mask all but the parts of a value that are safe to show for its type
+///
+/// - credit cards, phones and SSNs keep their last four digits
+/// - emails keep the first character and the domain (`j***@example.com`)
+/// - IPv4 addresses are truncated to their /24, IPv6 to their /48
+/// - API keys keep their first four characters
+pub(crate) fn partial_mask(redaction_type: &RedactionType, value: &str) -> String {
+    match redaction_type {
+        RedactionType::CreditCard | RedactionType::Phone | RedactionType::Ssn => {
+            keep_last_digits(value, 4)
+        }
+        RedactionType::Email => match value.split_once('@') {
+            Some((local, domain)) => {
+                let first = local.chars().next().map(String::from).unwrap_or_default();
+                format!("{first}{MASK}{MASK}{MASK}@{domain}")
+            }
+            None => mask(value),
+        },
+        RedactionType::IpAddress => truncate_ip(value).unwrap_or_else(|| mask(value)),
+        RedactionType::ApiKey => {
+            let prefix: String = value.chars().take(4).collect();
+            format!("{prefix}{MASK}{MASK}{MASK}{MASK}")
+        }
+        _ => mask(value),
+    }
+}
+
+/// Mask every digit except the last `keep`
+fn keep_last_digits(value: &str, keep: usize) -> String {
+    let total = value.chars().filter(|c| c.is_ascii_digit()).count();
+    let mut seen = 0;
+    value
+        .chars()
+        .map(|c| {
+            if !c.is_ascii_digit() {
+                return c;
+            }
+            seen += 1;
+            if seen + keep > total {
+                c
+            } else {
+                MASK
+            }
+        })
+        .collect()
+}
+
+/// Network prefix of an IP address: /24 for IPv4, /48 for IPv6
+///
+/// A CIDR range that is already wider is shown as it is.
+fn truncate_ip(value: &str) -> Option<String> {
+    let (address, prefix) = match value.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix.parse::<u8>().ok()?)),
+        None => (value, None),
+    };
+    let address = address.parse::<std::net::IpAddr>().ok()?;
+    let limit = if address.is_ipv4() { 24 } else { 48 };
+    if prefix.is_some_and(|prefix| prefix <= limit) {
+        return Some(value.to_string());
+    }
+    match address {
+        std::net::IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            Some(format!("{a}.{b}.{c}.0/24"))
+        }
+        std::net::IpAddr::V6(ip) => {
+            let s = ip.segments();
+            let prefix = std::net::Ipv6Addr::new(s[0], s[1], s[2], 0, 0, 0, 0, 0);
+            Some(format!("{prefix}/48"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_mask() {
+        let cases = [
+            (
+                RedactionType::CreditCard,
+                "4532-0151-1283-0366",
+                "****-****-****-0366",
+            ),
+            (RedactionType::Ssn, "123-45-6789", "***-**-6789"),
+            (RedactionType::Phone, "(415) 867-5309", "(***) ***-5309"),
+            (
+                RedactionType::Email,
+                "john.doe@example.com",
+                "j***@example.com",
+            ),
+            (RedactionType::IpAddress, "192.168.1.42", "192.168.1.0/24"),
+            (RedactionType::IpAddress, "10.20.30.0/28", "10.20.30.0/24"),
+            (RedactionType::IpAddress, "10.0.0.0/8", "10.0.0.0/8"),
+            (
+                RedactionType::IpAddress,
+                "2001:0db8:85a3:0000:0000:8a2e:0370:7334",
+                "2001:db8:85a3::/48",
+            ),
+            (RedactionType::ApiKey, "sk-abcdef1234567890", "sk-a****"),
+        ];
+
+        for (redaction_type, value, expected) in cases {
+            assert_eq!(partial_mask(&redaction_type, value), expected);
+        }
+    }
+
+    #[test]
+    fn test_mask_keeps_separators() {
+        assert_eq!(mask("123-45-6789"), "***-**-****");
+    }
+}
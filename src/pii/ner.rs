@@ -0,0 +1,738 @@
+//! Offline named-entity detection for names, street addresses and dates of birth
+//!
+//! Everything here is dictionaries and grammar, so it runs on the CPU with no
+//! model download. Names need a known first name followed by a capitalized
+//! word (or a title such as "Dr."), addresses need a house number and a street
+//! suffix, and dates are only treated as dates of birth next to words like
+//! "born" or "DOB".
+
+use super::rules::{compile, RuleMatch};
+use crate::config::PiiConfig;
+use crate::error::Result;
+use crate::types::RedactionType;
+use regex::Regex;
+
+/// Common first names, sorted for binary search
+const FIRST_NAMES: &[&str] = &[
+    "Aaron",
+    "Abigail",
+    "Adam",
+    "Ahmed",
+    "Aisha",
+    "Alan",
+    "Albert",
+    "Alexander",
+    "Alexis",
+    "Ali",
+    "Alice",
+    "Amanda",
+    "Amber",
+    "Amy",
+    "Ana",
+    "Andrea",
+    "Andrew",
+    "Angela",
+    "Ann",
+    "Anna",
+    "Anthony",
+    "Arthur",
+    "Ashley",
+    "Austin",
+    "Ava",
+    "Barbara",
+    "Benjamin",
+    "Betty",
+    "Beverly",
+    "Billy",
+    "Bobby",
+    "Brandon",
+    "Brenda",
+    "Brian",
+    "Brittany",
+    "Bruce",
+    "Bryan",
+    "Carl",
+    "Carlos",
+    "Carol",
+    "Carolyn",
+    "Catherine",
+    "Charles",
+    "Charlotte",
+    "Cheryl",
+    "Chloe",
+    "Christian",
+    "Christina",
+    "Christine",
+    "Christopher",
+    "Cynthia",
+    "Daniel",
+    "Danielle",
+    "David",
+    "Deborah",
+    "Debra",
+    "Denise",
+    "Dennis",
+    "Diana",
+    "Diane",
+    "Donald",
+    "Donna",
+    "Doris",
+    "Dorothy",
+    "Douglas",
+    "Dylan",
+    "Edward",
+    "Elijah",
+    "Elizabeth",
+    "Emily",
+    "Emma",
+    "Eric",
+    "Ethan",
+    "Eugene",
+    "Evelyn",
+    "Fatima",
+    "Frances",
+    "Frank",
+    "Gabriel",
+    "Gary",
+    "George",
+    "Gerald",
+    "Gloria",
+    "Grace",
+    "Gregory",
+    "Hannah",
+    "Harold",
+    "Heather",
+    "Helen",
+    "Henry",
+    "Hiroshi",
+    "Isabella",
+    "Jack",
+    "Jacob",
+    "Jacqueline",
+    "James",
+    "Jan",
+    "Jane",
+    "Janet",
+    "Janice",
+    "Jason",
+    "Jean",
+    "Jeffrey",
+    "Jennifer",
+    "Jeremy",
+    "Jerry",
+    "Jesse",
+    "Jessica",
+    "Joan",
+    "Joe",
+    "John",
+    "Jonathan",
+    "Jordan",
+    "Jose",
+    "Joseph",
+    "Joshua",
+    "Joyce",
+    "Juan",
+    "Judith",
+    "Judy",
+    "Julie",
+    "Justin",
+    "Karen",
+    "Katherine",
+    "Kathleen",
+    "Kathryn",
+    "Kayla",
+    "Keith",
+    "Kelly",
+    "Kenneth",
+    "Kevin",
+    "Kimberly",
+    "Kyle",
+    "Larry",
+    "Laura",
+    "Lauren",
+    "Lawrence",
+    "Liam",
+    "Linda",
+    "Lisa",
+    "Logan",
+    "Lori",
+    "Louis",
+    "Lucas",
+    "Lucia",
+    "Luis",
+    "Madison",
+    "Margaret",
+    "Maria",
+    "Marie",
+    "Marilyn",
+    "Mark",
+    "Martha",
+    "Mary",
+    "Mason",
+    "Matthew",
+    "Megan",
+    "Mei",
+    "Melissa",
+    "Mia",
+    "Michael",
+    "Michelle",
+    "Miguel",
+    "Mohammed",
+    "Muhammad",
+    "Nancy",
+    "Natalie",
+    "Nathan",
+    "Nicholas",
+    "Nicole",
+    "Noah",
+    "Oliver",
+    "Olivia",
+    "Pamela",
+    "Patricia",
+    "Patrick",
+    "Paul",
+    "Pedro",
+    "Peter",
+    "Philip",
+    "Priya",
+    "Rachel",
+    "Rahul",
+    "Raj",
+    "Ralph",
+    "Randy",
+    "Raymond",
+    "Rebecca",
+    "Richard",
+    "Robert",
+    "Roger",
+    "Ronald",
+    "Roy",
+    "Russell",
+    "Ruth",
+    "Ryan",
+    "Samantha",
+    "Samuel",
+    "Sandra",
+    "Sara",
+    "Sarah",
+    "Scott",
+    "Sean",
+    "Sharon",
+    "Shirley",
+    "Sofia",
+    "Sophia",
+    "Stephanie",
+    "Stephen",
+    "Steven",
+    "Susan",
+    "Teresa",
+    "Terry",
+    "Theresa",
+    "Thomas",
+    "Timothy",
+    "Tyler",
+    "Victoria",
+    "Vincent",
+    "Virginia",
+    "Walter",
+    "Wayne",
+    "Wei",
+    "William",
+    "Willie",
+    "Yuki",
+    "Zachary",
+];
+
+/// Common surnames, sorted for binary search
+const LAST_NAMES: &[&str] = &[
+    "Adams",
+    "Aguilar",
+    "Ahmed",
+    "Alexander",
+    "Allen",
+    "Alvarez",
+    "Anderson",
+    "Andrews",
+    "Armstrong",
+    "Arnold",
+    "Bailey",
+    "Baker",
+    "Barnes",
+    "Bell",
+    "Bennett",
+    "Berry",
+    "Black",
+    "Boyd",
+    "Bradley",
+    "Brooks",
+    "Brown",
+    "Bryant",
+    "Burns",
+    "Butler",
+    "Campbell",
+    "Carpenter",
+    "Carroll",
+    "Carter",
+    "Castillo",
+    "Castro",
+    "Chavez",
+    "Chen",
+    "Choi",
+    "Clark",
+    "Cole",
+    "Coleman",
+    "Collins",
+    "Connor",
+    "Cook",
+    "Cooper",
+    "Cox",
+    "Crawford",
+    "Cruz",
+    "Cunningham",
+    "Daniels",
+    "Davis",
+    "Diaz",
+    "Dixon",
+    "Doe",
+    "Dubois",
+    "Duncan",
+    "Dunn",
+    "Edwards",
+    "Elliott",
+    "Ellis",
+    "Evans",
+    "Ferguson",
+    "Fernandez",
+    "Ferrari",
+    "Fischer",
+    "Fisher",
+    "Flores",
+    "Ford",
+    "Foster",
+    "Fox",
+    "Freeman",
+    "Garcia",
+    "Gardner",
+    "Garza",
+    "Gibson",
+    "Gomez",
+    "Gonzales",
+    "Gonzalez",
+    "Gordon",
+    "Graham",
+    "Grant",
+    "Gray",
+    "Green",
+    "Griffin",
+    "Gutierrez",
+    "Guzman",
+    "Hall",
+    "Hamilton",
+    "Hansen",
+    "Harris",
+    "Harrison",
+    "Hart",
+    "Hawkins",
+    "Hayes",
+    "Henderson",
+    "Henry",
+    "Hernandez",
+    "Herrera",
+    "Hicks",
+    "Hill",
+    "Hoffman",
+    "Holmes",
+    "Howard",
+    "Huang",
+    "Hudson",
+    "Hughes",
+    "Hunt",
+    "Hunter",
+    "Hussain",
+    "Ivanov",
+    "Jackson",
+    "James",
+    "Jenkins",
+    "Jimenez",
+    "Johnson",
+    "Johnston",
+    "Jones",
+    "Jordan",
+    "Kelley",
+    "Kelly",
+    "Kennedy",
+    "Khan",
+    "Kim",
+    "King",
+    "Knight",
+    "Kowalski",
+    "Kumar",
+    "Lane",
+    "Laurent",
+    "Lawrence",
+    "Lee",
+    "Lewis",
+    "Li",
+    "Liu",
+    "Long",
+    "Lopez",
+    "Marshall",
+    "Martin",
+    "Martinez",
+    "Mason",
+    "Matthews",
+    "McDonald",
+    "Medina",
+    "Mendez",
+    "Mendoza",
+    "Meyer",
+    "Miller",
+    "Mills",
+    "Mitchell",
+    "Moore",
+    "Morales",
+    "Moreau",
+    "Morgan",
+    "Morris",
+    "Muller",
+    "Munoz",
+    "Murphy",
+    "Murray",
+    "Myers",
+    "Nelson",
+    "Nguyen",
+    "Nichols",
+    "Novak",
+    "Olson",
+    "Ortiz",
+    "Owens",
+    "Palmer",
+    "Park",
+    "Parker",
+    "Patel",
+    "Patterson",
+    "Payne",
+    "Pena",
+    "Perez",
+    "Perkins",
+    "Perry",
+    "Peters",
+    "Peterson",
+    "Phillips",
+    "Pierce",
+    "Porter",
+    "Powell",
+    "Price",
+    "Ramirez",
+    "Ramos",
+    "Ray",
+    "Reed",
+    "Reyes",
+    "Reynolds",
+    "Rice",
+    "Richards",
+    "Richardson",
+    "Riley",
+    "Rivera",
+    "Roberts",
+    "Robertson",
+    "Robinson",
+    "Rodriguez",
+    "Rogers",
+    "Romero",
+    "Rose",
+    "Ross",
+    "Rossi",
+    "Ruiz",
+    "Russell",
+    "Russo",
+    "Ryan",
+    "Salazar",
+    "Sanchez",
+    "Sanders",
+    "Sandoval",
+    "Santos",
+    "Sato",
+    "Schmidt",
+    "Schneider",
+    "Scott",
+    "Sharma",
+    "Shaw",
+    "Silva",
+    "Simmons",
+    "Simpson",
+    "Singh",
+    "Smirnov",
+    "Smith",
+    "Snyder",
+    "Soto",
+    "Spencer",
+    "Stephens",
+    "Stevens",
+    "Stewart",
+    "Stone",
+    "Sullivan",
+    "Suzuki",
+    "Tanaka",
+    "Taylor",
+    "Thomas",
+    "Thompson",
+    "Torres",
+    "Tran",
+    "Tucker",
+    "Turner",
+    "Vargas",
+    "Vasquez",
+    "Wagner",
+    "Walker",
+    "Wallace",
+    "Walsh",
+    "Wang",
+    "Ward",
+    "Warren",
+    "Washington",
+    "Watanabe",
+    "Watson",
+    "Weaver",
+    "Webb",
+    "Weber",
+    "Wells",
+    "West",
+    "White",
+    "Williams",
+    "Willis",
+    "Wilson",
+    "Wood",
+    "Woods",
+    "Wright",
+    "Wu",
+    "Yang",
+    "Young",
+    "Zhang",
+    "Zhao",
+    "Zhou",
+];
+
+/// Honorifics that mark the following capitalized words as a name
+const TITLES: &[&str] = &["Dr", "Miss", "Mr", "Mrs", "Ms", "Prof"];
+
+/// Words that mark a nearby date as a date of birth
+const BIRTH_KEYWORDS: &[&str] = &[
+    "birth date",
+    "birthdate",
+    "birthday",
+    "born",
+    "d.o.b",
+    "date of birth",
+    "dob",
+];
+
+/// Compiled entity grammars
+pub(super) struct EntityRecognizer {
+    word: Regex,
+    address: Regex,
+    date: Regex,
+}
+
+impl EntityRecognizer {
+    pub(super) fn new() -> Result<Self> {
+        Ok(Self {
+            // Capitalized words, including hyphenated and O'Brien-style names
+            word: compile(r"\b[A-Z][a-z]+(?:[-'][A-Z][a-z]+)*\b")?,
+            address: compile(
+                r"(?x)
+                \b\d{1,5}[A-Za-z]?                                  # house number
+                (?:\s+(?:[A-Z][a-z]+|\d+(?:st|nd|rd|th))){1,4}?     # street name
+                \s+(?:(?:Street|Avenue|Road|Boulevard|Lane|Drive|Court|Place|Square|Terrace
+                    |Way|Circle|Parkway|Highway)\b
+                  | (?:St|Ave|Rd|Blvd|Ln|Dr|Ct|Pl|Sq|Cir|Pkwy|Hwy)\b\.?)
+                (?:,?\s+(?:Apt|Apartment|Suite|Ste|Unit|Flat)\.?\s*\#?\w+)?
+                (?:,\s*[A-Z][a-z]+(?:\s+[A-Z][a-z]+){0,2})?         # city
+                (?:,?\s+[A-Z]{2}\b)?                                # state
+                (?:,?\s+(?P<postcode>\d{5}(?:-\d{4})?|[A-Z]{1,2}\d[A-Z\d]?\s?\d[A-Z]{2})\b)?
+                ",
+            )?,
+            date: compile(
+                r"(?xi)
+                \b(?:
+                    \d{1,2}[/.-]\d{1,2}[/.-](?:19|20)?\d{2}
+                  | (?:19|20)\d{2}-\d{2}-\d{2}
+                  | (?:jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?\s+\d{1,2}(?:st|nd|rd|th)?,?\s+(?:19|20)\d{2}
+                  | \d{1,2}(?:st|nd|rd|th)?\s+(?:jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?,?\s+(?:19|20)\d{2}
+                )\b",
+            )?,
+        })
+    }
+
+    /// Find the entity types enabled in `config`
+    pub(super) fn scan(&self, text: &str, config: &PiiConfig) -> Vec<RuleMatch> {
+        let mut matches = vec![];
+        if config.detect_names {
+            self.scan_names(text, &mut matches);
+        }
+        if config.detect_addresses {
+            for caps in self.address.captures_iter(text) {
+                let m = caps.get(0).unwrap();
+                matches.push(RuleMatch {
+                    rule: "street_address",
+                    redaction_type: RedactionType::Address,
+                    start: m.start(),
+                    end: m.end(),
+                    confidence: if caps.name("postcode").is_some() {
+                        0.9
+                    } else {
+                        0.75
+                    },
+                });
+            }
+        }
+        if config.detect_dates_of_birth {
+            for m in self.date.find_iter(text) {
+                if near_birth_keyword(text, m.start()) {
+                    matches.push(RuleMatch {
+                        rule: "date_of_birth",
+                        redaction_type: RedactionType::DateOfBirth,
+                        start: m.start(),
+                        end: m.end(),
+                        confidence: 0.9,
+                    });
+                }
+            }
+        }
+        matches
+    }
+
+    /// A known first name or a title, followed by capitalized words
+    fn scan_names(&self, text: &str, matches: &mut Vec<RuleMatch>) {
+        let words: Vec<_> = self.word.find_iter(text).collect();
+        let mut i = 0;
+        while i + 1 < words.len() {
+            let (word, next) = (words[i], words[i + 1]);
+            if !joins_name(&text[word.end()..next.start()]) {
+                i += 1;
+                continue;
+            }
+
+            let (start, end, confidence) = if TITLES.contains(&word.as_str()) {
+                // Mr. John Smith: include a second word if it follows
+                let end = match words.get(i + 2) {
+                    Some(last) if joins_name(&text[next.end()..last.start()]) => {
+                        i += 1;
+                        last.end()
+                    }
+                    _ => next.end(),
+                };
+                (next.start(), end, 0.85)
+            } else if FIRST_NAMES.binary_search(&word.as_str()).is_ok() {
+                let known_last = LAST_NAMES.binary_search(&next.as_str()).is_ok();
+                (word.start(), next.end(), if known_last { 0.9 } else { 0.6 })
+            } else {
+                i += 1;
+                continue;
+            };
+
+            matches.push(RuleMatch {
+                rule: "person_name",
+                redaction_type: RedactionType::PersonName,
+                start,
+                end,
+                confidence,
+            });
+            i += 2;
+        }
+    }
+}
+
+/// Whether the text between two words keeps them in one name: whitespace,
+/// optionally around a middle initial or after a title's period
+fn joins_name(gap: &str) -> bool {
+    let gap = gap.strip_prefix('.').unwrap_or(gap);
+    let trimmed = gap.trim();
+    let initial = trimmed.len() <= 2
+        && trimmed.starts_with(|c: char| c.is_ascii_uppercase())
+        && trimmed[1..].chars().all(|c| c == '.');
+    gap.starts_with(char::is_whitespace)
+        && gap.ends_with(char::is_whitespace)
+        && (trimmed.is_empty() || initial)
+}
+
+fn near_birth_keyword(text: &str, start: usize) -> bool {
+    let mut window_start = start.saturating_sub(24);
+    while !text.is_char_boundary(window_start) {
+        window_start += 1;
+    }
+    let window = text[window_start..start].to_lowercase();
+    BIRTH_KEYWORDS.iter().any(|k| window.contains(k))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(text: &str) -> Vec<(RedactionType, &str)> {
+        let config = PiiConfig {
+            detect_names: true,
+            detect_addresses: true,
+            detect_dates_of_birth: true,
+            ..Default::default()
+        };
+        EntityRecognizer::new()
+            .unwrap()
+            .scan(text, &config)
+            .iter()
+            .map(|m| (m.redaction_type.clone(), &text[m.start..m.end]))
+            .collect()
+    }
+
+    #[test]
+    fn test_dictionaries_are_sorted() {
+        for list in [FIRST_NAMES, LAST_NAMES] {
+            assert!(list.windows(2).all(|w| w[0] < w[1]));
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(
+            found("Please ask Sarah J. Connor or Dr. Watson."),
+            vec![
+                (RedactionType::PersonName, "Sarah J. Connor"),
+                (RedactionType::PersonName, "Watson"),
+            ]
+        );
+        assert_eq!(
+            found("Hello Mrs. Mary-Kate Olsen"),
+            vec![(RedactionType::PersonName, "Mary-Kate Olsen")]
+        );
+        assert!(found("The United States Postal Service").is_empty());
+    }
+
+    #[test]
+    fn test_addresses() {
+        assert_eq!(
+            found("Ship to 1600 Pennsylvania Avenue, Washington, DC 20500 today"),
+            vec![(
+                RedactionType::Address,
+                "1600 Pennsylvania Avenue, Washington, DC 20500"
+            )]
+        );
+        assert_eq!(
+            found("Office: 10 Downing Street, London SW1A 2AA"),
+            vec![(RedactionType::Address, "10 Downing Street, London SW1A 2AA")]
+        );
+        assert!(found("We shipped 12 Large Boxes").is_empty());
+    }
+
+    #[test]
+    fn test_dates_of_birth() {
+        assert_eq!(
+            found("DOB: 04/12/1987, account opened 2020-01-05"),
+            vec![(RedactionType::DateOfBirth, "04/12/1987")]
+        );
+        assert_eq!(
+            found("she was born on March 3rd, 1990"),
+            vec![(RedactionType::DateOfBirth, "March 3rd, 1990")]
+        );
+        assert!(found("Invoice dated 04/12/1987").is_empty());
+    }
+}
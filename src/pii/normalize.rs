@@ -0,0 +1,302 @@
+//! Undoing obfuscation before PII matching
+//!
+//! Detection runs on a normalized copy of the text: NFKC-normalized,
+//! without zero-width characters, with "at"/"dot" spellings turned back into
+//! `@` and `.`, and with spaced-out characters (`4 1 1 1 ...`) joined. Every
+//! byte of the copy remembers which bytes of the original it came from, so
+//! matches can be mapped back to the exact characters to redact.
+
+use super::rules::compile;
+use crate::error::Result;
+use regex::Regex;
+use std::borrow::Cow;
+use std::ops::Range;
+use unicode_normalization::UnicodeNormalization;
+
+/// Characters that render as nothing
+const ZERO_WIDTH: &[char] = &[
+    '\u{00AD}', // soft hyphen
+    '\u{180E}', // Mongolian vowel separator
+    '\u{200B}', // zero width space
+    '\u{200C}', // zero width non-joiner
+    '\u{200D}', // zero width joiner
+    '\u{2060}', // word joiner
+    '\u{FEFF}', // zero width no-break space
+];
+
+/// Fewest spaced-out characters that are joined back together
+const MIN_SPACED_RUN: usize = 4;
+
+/// Replace `range` of the current text with a string
+type Edit = (Range<usize>, Cow<'static, str>);
+
+/// Edits that undo one kind of obfuscation
+type Pass<'a> = &'a dyn Fn(&str) -> Vec<Edit>;
+
+/// Patterns for the obfuscations undone before matching
+pub(super) struct Normalizer {
+    /// `[at]`, `(dot)` and similar, with any spaces around them
+    bracketed: Regex,
+    /// `john at example dot com`
+    spelled_email: Regex,
+    /// The ` at ` and ` dot ` inside a spelled email
+    spelled_separator: Regex,
+    /// `john @ example . com`
+    spaced_email: Regex,
+    /// Single characters separated by single spaces
+    spaced_run: Regex,
+}
+
+impl Normalizer {
+    pub(super) fn new() -> Result<Self> {
+        Ok(Self {
+            bracketed: compile(
+                r"(?i)\[ *(at|dot) *\]|\( *(at|dot) *\)|\{ *(at|dot) *\}|< *(at|dot) *>",
+            )?,
+            spelled_email: compile(
+                r"(?i)[a-z0-9._%+-]+ +at +[a-z0-9-]+(?: +dot +[a-z0-9-]+)+(?-u:\b)",
+            )?,
+            spelled_separator: compile(r"(?i) +(at|dot) +")?,
+            spaced_email: compile(r"[A-Za-z0-9._%+-]+ *@ *[A-Za-z0-9-]+(?: *\. *[A-Za-z0-9-]+)+")?,
+            spaced_run: compile(r"(?:^| )[[:alnum:]@.+_-](?: [[:alnum:]@.+_-]){3,}(?: |$)")?,
+        })
+    }
+
+    /// Normalize `text`, or `None` if there is nothing to undo
+    pub(super) fn normalize(&self, text: &str) -> Option<Normalized> {
+        let passes: [Pass; 4] = [
+            &unicode_edits,
+            &|text| self.spelled_edits(text),
+            &|text| self.spaced_edits(text),
+            &|text| self.email_spacing_edits(text),
+        ];
+
+        let mut normalized: Option<Normalized> = None;
+        for pass in passes {
+            let edits = pass(normalized.as_ref().map_or(text, |n| n.text.as_str()));
+            if !edits.is_empty() {
+                let base = normalized.unwrap_or_else(|| Normalized::identity(text));
+                normalized = Some(base.apply(&edits));
+            }
+        }
+        normalized
+    }
+
+    /// `[at]`/`(dot)` anywhere, and ` at `/` dot ` in an email-shaped phrase
+    fn spelled_edits(&self, text: &str) -> Vec<Edit> {
+        let separator = |word: &str| {
+            if word.eq_ignore_ascii_case("at") {
+                Cow::Borrowed("@")
+            } else {
+                Cow::Borrowed(".")
+            }
+        };
+
+        let mut edits: Vec<Edit> = self
+            .bracketed
+            .captures_iter(text)
+            .map(|caps| {
+                // Take the spaces around the brackets too
+                let m = caps.get(0).unwrap();
+                let start = text[..m.start()].trim_end().len();
+                let end = text.len() - text[m.end()..].trim_start().len();
+                let word = caps.iter().skip(1).flatten().next().unwrap();
+                (start..end, separator(word.as_str()))
+            })
+            .collect();
+        for phrase in self.spelled_email.find_iter(text) {
+            // Whole words only, not the tail of "café at ..."
+            if text[..phrase.start()]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphanumeric)
+            {
+                continue;
+            }
+            for caps in self.spelled_separator.captures_iter(phrase.as_str()) {
+                let m = caps.get(0).unwrap();
+                let range = phrase.start() + m.start()..phrase.start() + m.end();
+                edits.push((range, separator(&caps[1])));
+            }
+        }
+        edits.sort_by_key(|(range, _)| range.start);
+        edits
+    }
+
+    /// Join runs like `4 1 1 1` or `j o h n @ x . c o m`
+    fn spaced_edits(&self, text: &str) -> Vec<Edit> {
+        let mut edits = vec![];
+        for run in self.spaced_run.find_iter(text) {
+            let start = run.start() + usize::from(run.as_str().starts_with(' '));
+            let inner = run.as_str().trim_matches(' ');
+            if inner.chars().filter(|c| c.is_alphanumeric()).count() < MIN_SPACED_RUN {
+                continue;
+            }
+            for (i, _) in inner.match_indices(' ') {
+                edits.push((start + i..start + i + 1, Cow::Borrowed("")));
+            }
+        }
+        edits
+    }
+
+    /// Drop the spaces in `john @ example . com`
+    fn email_spacing_edits(&self, text: &str) -> Vec<Edit> {
+        let mut edits = vec![];
+        for m in self.spaced_email.find_iter(text) {
+            for (i, _) in m.as_str().match_indices(' ') {
+                let start = m.start() + i;
+                edits.push((start..start + 1, Cow::Borrowed("")));
+            }
+        }
+        edits
+    }
+}
+
+/// Text prepared for matching, and where each of its bytes came from
+pub(super) struct Normalized {
+    pub(super) text: String,
+    /// Original byte range behind each byte of `text`
+    sources: Vec<Range<usize>>,
+}
+
+impl Normalized {
+    /// Original byte range of `start..end` in the normalized text
+    ///
+    /// An empty range maps to an empty range where its position came from.
+    pub(super) fn original(&self, start: usize, end: usize) -> (usize, usize) {
+        if start == end {
+            let at = match self.sources.get(start) {
+                Some(source) => source.start,
+                None => self.sources.last().map_or(0, |source| source.end),
+            };
+            return (at, at);
+        }
+        (self.sources[start].start, self.sources[end - 1].end)
+    }
+
+    /// `text` mapped onto itself
+    fn identity(text: &str) -> Self {
+        let mut sources = Vec::with_capacity(text.len());
+        for (i, c) in text.char_indices() {
+            let source = i..i + c.len_utf8();
+            sources.extend(std::iter::repeat_n(source, c.len_utf8()));
+        }
+        Self {
+            text: text.to_string(),
+            sources,
+        }
+    }
+
+    /// Apply edits sorted by start; an edit overlapping the previous one is
+    /// skipped
+    ///
+    /// Replacement bytes map to everything the edited range came from.
+    fn apply(self, edits: &[Edit]) -> Self {
+        let mut text = String::with_capacity(self.text.len());
+        let mut sources = Vec::with_capacity(self.sources.len());
+        let mut last = 0;
+        for (range, replacement) in edits.iter().filter(|(range, _)| !range.is_empty()) {
+            if range.start < last {
+                continue;
+            }
+            text.push_str(&self.text[last..range.start]);
+            sources.extend_from_slice(&self.sources[last..range.start]);
+            let source = self.sources[range.start].start..self.sources[range.end - 1].end;
+            text.push_str(replacement);
+            sources.extend(std::iter::repeat_n(source, replacement.len()));
+            last = range.end;
+        }
+        text.push_str(&self.text[last..]);
+        sources.extend_from_slice(&self.sources[last..]);
+        Self { text, sources }
+    }
+}
+
+/// NFKC-normalize each character and drop zero-width ones
+///
+/// Characters are normalized one at a time so every output byte maps to
+/// a single input character; that is enough to turn full-width digits and
+/// letters, ligatures and circled numbers into plain ASCII.
+fn unicode_edits(text: &str) -> Vec<Edit> {
+    let mut edits = vec![];
+    if text.is_ascii() {
+        return edits;
+    }
+    for (i, c) in text.char_indices().filter(|(_, c)| !c.is_ascii()) {
+        let range = i..i + c.len_utf8();
+        if ZERO_WIDTH.contains(&c) {
+            edits.push((range, Cow::Borrowed("")));
+            continue;
+        }
+        let normalized: String = std::iter::once(c).nfkc().collect();
+        if normalized.chars().ne([c]) {
+            edits.push((range, Cow::Owned(normalized)));
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The normalized text, and the original text behind `value` in it
+    fn normalize<'a>(text: &'a str, value: &str) -> (String, &'a str) {
+        let normalized = Normalizer::new()
+            .unwrap()
+            .normalize(text)
+            .expect("nothing to normalize");
+        let start = normalized.text.find(value).expect("value not found");
+        let (start, end) = normalized.original(start, start + value.len());
+        (normalized.text, &text[start..end])
+    }
+
+    #[test]
+    fn test_unicode() {
+        let text = "SSN １２３-４５-６７８９ and 123\u{200B}-45-\u{FEFF}6789.";
+        let (normalized, original) = normalize(text, "123-45-6789");
+        assert_eq!(normalized, "SSN 123-45-6789 and 123-45-6789.");
+        assert_eq!(original, "１２３-４５-６７８９");
+    }
+
+    #[test]
+    fn test_empty_range() {
+        let normalized = Normalizer::new().unwrap().normalize("ａb").unwrap();
+        assert_eq!(normalized.text, "ab");
+        assert_eq!(normalized.original(0, 0), (0, 0));
+        assert_eq!(normalized.original(1, 1), (3, 3));
+        assert_eq!(normalized.original(2, 2), (4, 4));
+    }
+
+    #[test]
+    fn test_spelled_separators() {
+        let (normalized, original) = normalize(
+            "Write to john at example dot com or jane [at] acme (dot) io",
+            "john@example.com",
+        );
+        assert_eq!(normalized, "Write to john@example.com or jane@acme.io");
+        assert_eq!(original, "john at example dot com");
+
+        // "at" alone is just a word
+        assert!(Normalizer::new()
+            .unwrap()
+            .normalize("Meet at noon, then dot the i's")
+            .is_none());
+    }
+
+    #[test]
+    fn test_spaced_characters() {
+        let (normalized, original) = normalize("mail j o h n @ example . com", "john@example.com");
+        assert_eq!(normalized, "mail john@example.com");
+        assert_eq!(original, "j o h n @ example . com");
+
+        let (normalized, _) = normalize("card 4 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 ok", "4111");
+        assert_eq!(normalized, "card 4111111111111111 ok");
+
+        // Short runs and lists stay as they are
+        assert!(Normalizer::new()
+            .unwrap()
+            .normalize("I am a b c person - - -")
+            .is_none());
+    }
+}
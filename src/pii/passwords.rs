@@ -0,0 +1,149 @@
+//! Password detection from key/value and natural-language context
+//!
+//! Only the password value is redacted, so `password=[REDACTED:Password]`
+//! keeps the surrounding structure readable.
+
+use super::rules::Rule;
+use crate::types::RedactionType;
+
+/// Password rule catalog
+pub(super) const RULES: &[Rule] = &[
+    // password=..., "pwd": "...", DB_PASSWORD=..., Password=...; (ODBC)
+    Rule {
+        id: "password_assignment",
+        redaction_type: RedactionType::Password,
+        pattern: r#"(?i)[A-Za-z0-9_.-]*(?:password|passwd|pwd|passphrase)["']?\s*[:=]\s*(?:"([^"\r\n]+)"|'([^'\r\n]+)'|([^\s"',;&]+))"#,
+        validate: Some(not_a_placeholder),
+        confidence: 0.9,
+    },
+    // --password hunter2
+    Rule {
+        id: "password_flag",
+        redaction_type: RedactionType::Password,
+        pattern: r#"(?i)--pass(?:word)?\s+(?:"([^"\r\n]+)"|'([^'\r\n]+)'|([^\s"'-][^\s"']*))"#,
+        validate: None,
+        confidence: 0.9,
+    },
+    // .netrc: machine example.com login alice password hunter2
+    Rule {
+        id: "netrc_password",
+        redaction_type: RedactionType::Password,
+        pattern: r"(?i)\blogin\s+\S+\s+password\s+(\S+)",
+        validate: None,
+        confidence: 0.95,
+    },
+    // my password is hunter2 / the password for the VPN was "hunter2"
+    Rule {
+        id: "password_phrase",
+        redaction_type: RedactionType::Password,
+        pattern: r#"(?i)\b(?:password|passwd|passcode|passphrase|pw)\b(?:\s+(?:for|to|of|on)(?:\s+(?:the|my|our|your))?\s+[\w.-]+)?\s+(?:is|was)\b\s*:?\s*["'`]?([^\s"'`]*[^\s"'`.,;:!?)])"#,
+        validate: Some(not_a_description),
+        confidence: 0.75,
+    },
+];
+
+/// Words that follow "password is" when describing rather than giving one
+const DESCRIPTIONS: &[&str] = &[
+    "a",
+    "also",
+    "an",
+    "being",
+    "case-sensitive",
+    "changed",
+    "correct",
+    "different",
+    "empty",
+    "encrypted",
+    "expired",
+    "hashed",
+    "in",
+    "incorrect",
+    "invalid",
+    "it",
+    "missing",
+    "my",
+    "not",
+    "now",
+    "only",
+    "optional",
+    "our",
+    "required",
+    "reset",
+    "same",
+    "saved",
+    "secure",
+    "sensitive",
+    "set",
+    "still",
+    "stored",
+    "strong",
+    "that",
+    "the",
+    "this",
+    "too",
+    "very",
+    "weak",
+    "wrong",
+    "your",
+];
+
+/// Skip empty templates such as `password: ${DB_PASSWORD}` or `password=<your password>`
+fn not_a_placeholder(value: &str) -> bool {
+    !(value.starts_with("${") || value.starts_with('<') || value.chars().all(|c| c == '*'))
+}
+
+/// Skip prose like "the password is incorrect"
+fn not_a_description(value: &str) -> bool {
+    value.len() >= 3 && !DESCRIPTIONS.contains(&value.to_ascii_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rules::RuleScanner;
+    use super::*;
+
+    fn passwords(text: &str) -> Vec<&str> {
+        RuleScanner::new(RULES)
+            .unwrap()
+            .scan(text, &[true; RULES.len()])
+            .iter()
+            .map(|m| &text[m.start..m.end])
+            .collect()
+    }
+
+    #[test]
+    fn test_structured_inputs() {
+        assert_eq!(
+            passwords(r#"{"user": "alice", "pwd": "s3cr3t pass"}"#),
+            vec!["s3cr3t pass"]
+        );
+        assert_eq!(
+            passwords("database:\n  user: app\n  password: hunter2\n"),
+            vec!["hunter2"]
+        );
+        assert_eq!(
+            passwords("export DB_PASSWORD=hunter2 && mysql --password 'p4ss word'"),
+            vec!["hunter2", "p4ss word"]
+        );
+        assert_eq!(
+            passwords("Server=db;User Id=app;Password=Tr0ub4dor;"),
+            vec!["Tr0ub4dor"]
+        );
+        assert_eq!(
+            passwords("machine api.example.com\n  login alice\n  password hunter2\n"),
+            vec!["hunter2"]
+        );
+        assert!(passwords("password: ${DB_PASSWORD}").is_empty());
+    }
+
+    #[test]
+    fn test_prose() {
+        assert_eq!(passwords("My password is hunter2."), vec!["hunter2"]);
+        assert_eq!(
+            passwords("the password for the wifi was \"correct-horse\""),
+            vec!["correct-horse"]
+        );
+        assert!(passwords("Sorry, the password is incorrect").is_empty());
+        assert!(passwords("Your password is too short").is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+//! Phone number validation and E.164 normalization
+//!
+//! Numbers with an international prefix (`+` or `00`) are checked against the
+//! national number length of their country calling code; numbers without one
+//! must be valid NANP numbers. Bare digit runs only count as phones when
+//! nothing nearby says they are an order, invoice or account number.
+
+/// A validated phone number
+pub(super) struct PhoneNumber {
+    /// Length of the matched text, which may drop trailing digit groups
+    pub(super) len: usize,
+    /// Normalized form, e.g. `+442079460958`
+    pub(super) e164: String,
+    /// Confidence before context keywords are applied
+    pub(super) confidence: f32,
+}
+
+/// National significant number lengths by country calling code
+const COUNTRIES: &[(&str, usize, usize)] = &[
+    ("1", 10, 10),
+    ("7", 10, 10),
+    ("20", 8, 10),
+    ("27", 9, 9),
+    ("30", 10, 10),
+    ("31", 9, 9),
+    ("32", 8, 9),
+    ("33", 9, 9),
+    ("34", 9, 9),
+    ("36", 8, 9),
+    ("39", 6, 11),
+    ("40", 9, 9),
+    ("41", 9, 9),
+    ("43", 4, 13),
+    ("44", 9, 10),
+    ("45", 8, 8),
+    ("46", 7, 13),
+    ("47", 8, 8),
+    ("48", 9, 9),
+    ("49", 6, 13),
+    ("51", 8, 9),
+    ("52", 10, 10),
+    ("54", 10, 11),
+    ("55", 10, 11),
+    ("56", 9, 9),
+    ("57", 10, 10),
+    ("60", 8, 10),
+    ("61", 9, 9),
+    ("62", 8, 12),
+    ("63", 10, 10),
+    ("64", 8, 10),
+    ("65", 8, 8),
+    ("66", 8, 9),
+    ("81", 9, 10),
+    ("82", 8, 10),
+    ("84", 9, 10),
+    ("86", 10, 11),
+    ("90", 10, 10),
+    ("91", 10, 10),
+    ("92", 9, 10),
+    ("234", 8, 10),
+    ("254", 9, 9),
+    ("351", 9, 9),
+    ("352", 4, 11),
+    ("353", 7, 9),
+    ("358", 5, 12),
+    ("380", 9, 9),
+    ("420", 9, 9),
+    ("852", 8, 8),
+    ("886", 9, 9),
+    ("966", 9, 9),
+    ("971", 8, 9),
+    ("972", 8, 9),
+];
+
+/// Words that make a nearby digit run a phone number
+pub(super) const PHONE_KEYWORDS: &[&str] = &[
+    "call",
+    "cell",
+    "contact",
+    "fax",
+    "mob",
+    "mobile",
+    "phone",
+    "sms",
+    "tel",
+    "telephone",
+    "text",
+    "whatsapp",
+];
+
+/// Words that make a nearby digit run a reference number
+pub(super) const REFERENCE_KEYWORDS: &[&str] = &[
+    "account",
+    "acct",
+    "case",
+    "confirmation",
+    "invoice",
+    "order",
+    "po",
+    "ref",
+    "reference",
+    "serial",
+    "sku",
+    "ticket",
+    "tracking",
+    "transaction",
+];
+
+/// Validate a phone candidate found at `start` in `text`
+pub(super) fn parse(text: &str, start: usize, candidate: &str) -> Option<PhoneNumber> {
+    if !plausible_context(&text[..start], candidate) {
+        return None;
+    }
+
+    let (prefix_len, rest) = if let Some(rest) = candidate.strip_prefix('+') {
+        (1, rest)
+    } else if let Some(rest) = candidate.strip_prefix("00") {
+        (2, rest)
+    } else {
+        let digits: String = candidate.chars().filter(char::is_ascii_digit).collect();
+        let national = match digits.strip_prefix('1') {
+            Some(national) if digits.len() == 11 => national,
+            _ => &digits,
+        };
+        let formatted = candidate.contains(|c: char| !c.is_ascii_digit());
+        return valid_nanp(national).then(|| PhoneNumber {
+            len: candidate.len(),
+            e164: format!("+1{national}"),
+            confidence: if formatted { 0.8 } else { 0.6 },
+        });
+    };
+
+    // Collect digits, skipping a "(0)" trunk prefix, and note where each
+    // digit group ends so trailing groups can be dropped if the number is too long
+    let mut digits = String::new();
+    let mut group_ends = vec![];
+    let mut chars = rest.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if !digits.is_empty() && rest[i..].starts_with("(0)") {
+            chars.nth(1);
+            continue;
+        }
+        if c.is_ascii_digit() {
+            digits.push(c);
+            if !chars.peek().is_some_and(|(_, next)| next.is_ascii_digit()) {
+                group_ends.push((prefix_len + i + 1, digits.len()));
+            }
+        }
+    }
+
+    let followed_by_digit =
+        |len: usize| text[start + len..].starts_with(|c: char| c.is_ascii_digit());
+    group_ends
+        .into_iter()
+        .rev()
+        .filter(|&(len, _)| !followed_by_digit(len))
+        .find_map(|(len, count)| {
+            international(&digits[..count]).map(|e164| PhoneNumber {
+                len,
+                e164,
+                confidence: 0.9,
+            })
+        })
+}
+
+/// E.164 form of a number given with its country calling code
+fn international(digits: &str) -> Option<String> {
+    for code_len in 1..=3 {
+        if digits.len() <= code_len {
+            break;
+        }
+        let (code, national) = digits.split_at(code_len);
+        // Calling codes are prefix-free, so the first hit is the only one
+        if let Some(&(_, min, max)) = COUNTRIES.iter().find(|(c, _, _)| *c == code) {
+            let valid = (min..=max).contains(&national.len())
+                && !national.starts_with('0')
+                && (code != "1" || valid_nanp(national));
+            return valid.then(|| format!("+{digits}"));
+        }
+    }
+    None
+}
+
+/// NANP: area code and exchange start with 2-9, and N11 codes are not areas
+fn valid_nanp(national: &str) -> bool {
+    let bytes = national.as_bytes();
+    bytes.len() == 10 && bytes[0] >= b'2' && bytes[3] >= b'2' && &national[1..3] != "11"
+}
+
+/// Decide from the preceding words whether a candidate can be a phone number
+fn plausible_context(before: &str, candidate: &str) -> bool {
+    let mut window_start = before.len().saturating_sub(24);
+    while !before.is_char_boundary(window_start) {
+        window_start += 1;
+    }
+    let window = before[window_start..].to_lowercase();
+    if window.trim_end().ends_with('#') {
+        return false;
+    }
+
+    // The keyword closest to the number decides
+    let keyword = window
+        .split(|c: char| !c.is_alphanumeric())
+        .rev()
+        .find(|word| PHONE_KEYWORDS.contains(word) || REFERENCE_KEYWORDS.contains(word));
+    match keyword {
+        Some(word) => PHONE_KEYWORDS.contains(&word),
+        // Without context, only formatted numbers count
+        None => candidate.contains(|c: char| !c.is_ascii_digit()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn e164(text: &str) -> Option<String> {
+        let start = text.find(|c: char| c == '+' || c == '(' || c.is_ascii_digit())?;
+        let end = text.rfind(|c: char| c.is_ascii_digit())? + 1;
+        parse(text, start, &text[start..end]).map(|phone| phone.e164)
+    }
+
+    #[test]
+    fn test_international_numbers() {
+        let cases = [
+            ("+44 20 7946 0958", "+442079460958"),
+            ("+44 (0)20 7946 0958", "+442079460958"),
+            ("+81 3-1234-5678", "+81312345678"),
+            ("0049 30 901820", "+4930901820"),
+            ("+1 (415) 867-5309", "+14158675309"),
+            ("(415) 867-5309", "+14158675309"),
+            ("1-415-867-5309", "+14158675309"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(e164(text).as_deref(), Some(expected), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_lengths_and_codes() {
+        // Too short for the UK, unassigned code, NANP exchange starting with 1
+        for text in ["+44 20 7946", "+999 1234 5678", "+1 415 123 4567"] {
+            assert_eq!(e164(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_trailing_groups_are_dropped() {
+        let text = "+44 20 7946 0958 12";
+        let phone = parse(text, 0, text).unwrap();
+        assert_eq!(&text[..phone.len], "+44 20 7946 0958");
+    }
+
+    #[test]
+    fn test_reference_numbers_are_skipped() {
+        assert_eq!(e164("Order 4158675309 shipped"), None);
+        assert_eq!(e164("Invoice #415-867-5309"), None);
+        assert_eq!(e164("4158675309"), None);
+        assert_eq!(e164("Call me: 4158675309").as_deref(), Some("+14158675309"));
+    }
+}
@@ -0,0 +1,332 @@
+//! Keyed replacements: pseudonyms, hashes and format-preserving values
+//!
+//! Each fake is derived from HMAC-SHA256(secret, scope, type, value), so the
+//! same value maps to the same fake for the whole session. Fakes are drawn
+//! from ranges reserved for documentation or never issued (example.com
+//! domains, 555-01xx phone numbers, 9xx SSNs, 2001:db8::/32), which lets
+//! [`is_pseudonym`] recognise them when the model echoes them back.
+
+use super::luhn_check;
+use crate::types::RedactionType;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+const FIRST_NAMES: [&str; 16] = [
+    "alex", "jordan", "taylor", "morgan", "casey", "riley", "jamie", "avery", "quinn", "parker",
+    "rowan", "sage", "drew", "emery", "finley", "harper",
+];
+
+const LAST_NAMES: [&str; 16] = [
+    "smith", "lee", "garcia", "brown", "nguyen", "miller", "davis", "lopez", "wilson", "moore",
+    "clark", "lewis", "walker", "young", "hall", "king",
+];
+
+/// Reserved second-level domains (RFC 2606)
+const EMAIL_DOMAINS: [&str; 3] = ["example.com", "example.net", "example.org"];
+
+/// IPv4 documentation ranges (RFC 5737)
+const IPV4_PREFIXES: [&str; 3] = ["192.0.2", "198.51.100", "203.0.113"];
+
+/// Issuer prefix used for fake card numbers
+const CARD_PREFIX: &str = "400000";
+
+/// Generates keyed, deterministic replacements
+pub(crate) struct Pseudonymizer {
+    key: Vec<u8>,
+}
+
+impl Pseudonymizer {
+    /// Create a pseudonymizer keyed by `secret`
+    pub(crate) fn new(secret: &[u8]) -> Self {
+        Self {
+            key: secret.to_vec(),
+        }
+    }
+
+    /// Fake for `value` within `scope`
+    pub(crate) fn pseudonym(
+        &self,
+        scope: &str,
+        redaction_type: &RedactionType,
+        value: &str,
+    ) -> String {
+        let mut bytes = ByteStream::new(self.digest(scope, redaction_type, value));
+
+        match redaction_type {
+            RedactionType::Email => format!(
+                "{}.{}{}@{}",
+                FIRST_NAMES[bytes.below(16)],
+                LAST_NAMES[bytes.below(16)],
+                100 + bytes.below(900),
+                EMAIL_DOMAINS[bytes.below(3)]
+            ),
+            RedactionType::Phone => {
+                // A 555-01xx number in the last ten digits, with a country
+                // code for longer numbers
+                let national = format!(
+                    "{}{:02}55501{}{}",
+                    2 + bytes.below(8),
+                    bytes.below(100),
+                    bytes.below(10),
+                    bytes.below(10)
+                );
+                let count = digit_count(value);
+                let digits = match count {
+                    0..=10 => national[10 - count..].to_string(),
+                    11 => format!("1{national}"),
+                    _ => {
+                        let mut digits = (1 + bytes.below(9)).to_string();
+                        while digits.len() + national.len() < count {
+                            digits.push(char::from(b'0' + bytes.below(10) as u8));
+                        }
+                        digits + &national
+                    }
+                };
+                reformat(value, &digits)
+            }
+            RedactionType::Ssn => {
+                let digits = format!(
+                    "9{:02}{:02}{:04}",
+                    bytes.below(100),
+                    1 + bytes.below(99),
+                    1 + bytes.below(9999)
+                );
+                reformat(value, &digits)
+            }
+            RedactionType::CreditCard => {
+                let mut digits = CARD_PREFIX.to_string();
+                while digits.len() + 1 < digit_count(value) {
+                    digits.push(char::from(b'0' + bytes.below(10) as u8));
+                }
+                digits.push(luhn_check_digit(&digits));
+                reformat(value, &digits)
+            }
+            RedactionType::IpAddress if value.contains(':') => {
+                let groups: Vec<String> = (0..6)
+                    .map(|_| format!("{:x}", bytes.below(0x10000)))
+                    .collect();
+                format!("2001:db8:{}", groups.join(":"))
+            }
+            RedactionType::IpAddress => {
+                format!("{}.{}", IPV4_PREFIXES[bytes.below(3)], 1 + bytes.below(254))
+            }
+            _ => format!(
+                "{}_{:04x}{:04x}",
+                redaction_type.token_label(),
+                bytes.below(0x10000),
+                bytes.below(0x10000)
+            ),
+        }
+    }
+
+    /// Keyed hash of `value`, e.g. `[API_KEY:3f9a1c2b4d5e6f70]`
+    ///
+    /// Not scoped to a session, so the same value hashes the same everywhere.
+    pub(crate) fn keyed_hash(&self, redaction_type: &RedactionType, value: &str) -> String {
+        let digest = self.digest("hash", redaction_type, value);
+        let hex: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+        format!("[{}:{hex}]", redaction_type.token_label())
+    }
+
+    /// Replace each digit with a digit and each ASCII letter with a letter
+    /// of the same case, keeping everything else
+    pub(crate) fn format_preserving(&self, redaction_type: &RedactionType, value: &str) -> String {
+        let mut block = 0u32;
+        let mut bytes = ByteStream::new(self.digest("fpe/0", redaction_type, value));
+        value
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                // 16 characters per digest, then move on to the next block
+                if i > 0 && i % 16 == 0 {
+                    block += 1;
+                    bytes = ByteStream::new(self.digest(
+                        &format!("fpe/{block}"),
+                        redaction_type,
+                        value,
+                    ));
+                }
+                let n = bytes.below(26 * 10);
+                match c {
+                    '0'..='9' => char::from(b'0' + (n % 10) as u8),
+                    'a'..='z' => char::from(b'a' + (n % 26) as u8),
+                    'A'..='Z' => char::from(b'A' + (n % 26) as u8),
+                    _ => c,
+                }
+            })
+            .collect()
+    }
+
+    fn digest(&self, scope: &str, redaction_type: &RedactionType, value: &str) -> [u8; 32] {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        for part in [scope, redaction_type.token_label(), value] {
+            mac.update(part.as_bytes());
+            mac.update(&[0]);
+        }
+        mac.finalize().into_bytes().into()
+    }
+}
+
+/// Whether `value` lies in the range fakes of this type are drawn from
+pub(crate) fn is_pseudonym(redaction_type: &RedactionType, value: &str) -> bool {
+    let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+    match redaction_type {
+        RedactionType::Email => value.rsplit_once('@').is_some_and(|(_, domain)| {
+            EMAIL_DOMAINS.contains(&domain.to_ascii_lowercase().as_str())
+        }),
+        RedactionType::Phone => {
+            digits.len() >= 7 && digits[digits.len() - 7..].starts_with("55501")
+        }
+        RedactionType::Ssn => digits.len() == 9 && digits.starts_with('9'),
+        RedactionType::CreditCard => digits.starts_with(CARD_PREFIX) && luhn_check(&digits),
+        RedactionType::IpAddress => {
+            let lower = value.to_ascii_lowercase();
+            lower.starts_with("2001:db8:")
+                || lower.starts_with("2001:0db8:")
+                || IPV4_PREFIXES.iter().any(|p| {
+                    lower
+                        .strip_prefix(p)
+                        .is_some_and(|rest| rest.starts_with('.'))
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Reads small random numbers off a digest
+struct ByteStream {
+    bytes: [u8; 32],
+    pos: usize,
+}
+
+impl ByteStream {
+    fn new(bytes: [u8; 32]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    /// A number in `0..n` (n ≤ 65536)
+    fn below(&mut self, n: usize) -> usize {
+        let hi = self.bytes[self.pos % 32] as usize;
+        let lo = self.bytes[(self.pos + 1) % 32] as usize;
+        self.pos += 2;
+        ((hi << 8) | lo) % n
+    }
+}
+
+fn digit_count(value: &str) -> usize {
+    value.chars().filter(|c| c.is_ascii_digit()).count()
+}
+
+/// Write `digits` into the digit positions of `template`, keeping separators
+///
+/// `digits` should have one digit per digit of `template`; an original
+/// digit is never kept, even if it runs short.
+fn reformat(template: &str, digits: &str) -> String {
+    let mut digits = digits.chars();
+    let mut out: String = template
+        .chars()
+        .map(|c| {
+            if c.is_ascii_digit() {
+                digits.next().unwrap_or('0')
+            } else {
+                c
+            }
+        })
+        .collect();
+    out.extend(digits);
+    out
+}
+
+/// Check digit that makes `payload` + digit pass the Luhn check
+fn luhn_check_digit(payload: &str) -> char {
+    (b'0'..=b'9')
+        .map(char::from)
+        .find(|d| luhn_check(&format!("{payload}{d}")))
+        .unwrap_or('0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_within_scope() {
+        let p = Pseudonymizer::new(b"secret");
+        let a = p.pseudonym("session-1", &RedactionType::Email, "jane@acme.com");
+        let b = p.pseudonym("session-1", &RedactionType::Email, "jane@acme.com");
+        let other_scope = p.pseudonym("session-2", &RedactionType::Email, "jane@acme.com");
+        let other_key = Pseudonymizer::new(b"other").pseudonym(
+            "session-1",
+            &RedactionType::Email,
+            "jane@acme.com",
+        );
+
+        assert_eq!(a, b);
+        assert_ne!(a, other_scope);
+        assert_ne!(a, other_key);
+    }
+
+    #[test]
+    fn test_fakes_keep_format_and_are_recognised() {
+        let p = Pseudonymizer::new(b"secret");
+        let cases = [
+            (RedactionType::Email, "jane@acme.com"),
+            (RedactionType::Phone, "(415) 867-5309"),
+            (RedactionType::Phone, "+1-415-867-5309"),
+            (RedactionType::Phone, "+44 20 7946 0958"),
+            (RedactionType::Ssn, "123-45-6789"),
+            (RedactionType::CreditCard, "4532-0151-1283-0366"),
+            (RedactionType::IpAddress, "10.1.2.3"),
+            (RedactionType::IpAddress, "fe80:0:0:0:202:b3ff:fe1e:8329"),
+        ];
+
+        for (redaction_type, value) in cases {
+            let fake = p.pseudonym("s", &redaction_type, value);
+            assert_ne!(fake, value);
+            assert!(is_pseudonym(&redaction_type, &fake), "{fake}");
+            assert!(!is_pseudonym(&redaction_type, value), "{value}");
+        }
+
+        let phone = p.pseudonym("s", &RedactionType::Phone, "(415) 867-5309");
+        assert!(
+            phone.starts_with('(') && phone.contains(") 555-01"),
+            "{phone}"
+        );
+        // Every digit of a long international number is replaced
+        let phone = p.pseudonym("s", &RedactionType::Phone, "+44 20 7946 0958");
+        assert_eq!(phone.len(), "+44 20 7946 0958".len());
+        assert_eq!(digit_count(&phone), 12);
+        assert!(
+            phone.starts_with('+') && !phone.ends_with("0958"),
+            "{phone}"
+        );
+        let card = p.pseudonym("s", &RedactionType::CreditCard, "4532-0151-1283-0366");
+        assert_eq!(card.len(), 19);
+        assert!(luhn_check(&card.replace('-', "")));
+    }
+
+    #[test]
+    fn test_keyed_hash_and_format_preserving() {
+        let p = Pseudonymizer::new(b"secret");
+        let hash = p.keyed_hash(&RedactionType::ApiKey, "sk-abcdef1234567890");
+        assert_eq!(
+            hash,
+            p.keyed_hash(&RedactionType::ApiKey, "sk-abcdef1234567890")
+        );
+        assert!(hash.starts_with("[API_KEY:") && hash.len() == "[API_KEY:]".len() + 16);
+
+        let value = "AB12-cd34-5678-9012-efGH-ijkl";
+        let fpe = p.format_preserving(&RedactionType::OtherPii, value);
+        assert_ne!(fpe, value);
+        assert_eq!(fpe.len(), value.len());
+        for (a, b) in value.chars().zip(fpe.chars()) {
+            assert_eq!(a.is_ascii_digit(), b.is_ascii_digit());
+            assert_eq!(a.is_ascii_uppercase(), b.is_ascii_uppercase());
+            assert_eq!(a.is_ascii_lowercase(), b.is_ascii_lowercase());
+            if !a.is_ascii_alphanumeric() {
+                assert_eq!(a, b);
+            }
+        }
+    }
+}
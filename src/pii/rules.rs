@@ -0,0 +1,153 @@
+//! Regex rule catalogs shared by the secret, password and locale detectors
+//!
+//! Each rule pairs a regex with an optional validator (checksum, structure
+//! or denylist) that keeps look-alikes from being flagged. When a rule's
+//! regex has capture groups, only the first group that matched is redacted.
+//!
+//! Scanning every regex over a large input is what dominates detection
+//! time, so all detection regexes also go into one [`Prefilter`]: a single
+//! `RegexSet` pass tells which of them occur at all, and only those are run
+//! to find match positions.
+
+use crate::error::{GuardError, Result};
+use crate::types::RedactionType;
+use regex::{Captures, Match, Regex, RegexSet, RegexSetBuilder};
+use std::ops::Range;
+
+/// A detection rule
+pub(super) struct Rule {
+    /// Rule ID reported as the redaction's sub-kind
+    pub(super) id: &'static str,
+    /// Type reported for matches
+    pub(super) redaction_type: RedactionType,
+    /// Pattern; the first capture group that matched, if any, is the part to redact
+    pub(super) pattern: &'static str,
+    /// Extra validation of the redacted part
+    pub(super) validate: Option<fn(&str) -> bool>,
+    /// Confidence of a match before context keywords are applied
+    pub(super) confidence: f32,
+}
+
+/// A value found by a rule
+pub(super) struct RuleMatch {
+    pub(super) rule: &'static str,
+    pub(super) redaction_type: RedactionType,
+    pub(super) start: usize,
+    pub(super) end: usize,
+    pub(super) confidence: f32,
+}
+
+/// Compiled rules
+pub(super) struct RuleScanner {
+    rules: Vec<(&'static Rule, Regex)>,
+}
+
+impl RuleScanner {
+    /// Compile a set of rules
+    pub(super) fn new(rules: impl IntoIterator<Item = &'static Rule>) -> Result<Self> {
+        Ok(Self {
+            rules: rules
+                .into_iter()
+                .map(|rule| Ok((rule, compile(rule.pattern)?)))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Patterns to register with the prefilter, in rule order
+    pub(super) fn patterns(&self) -> Vec<&str> {
+        self.rules.iter().map(|(rule, _)| rule.pattern).collect()
+    }
+
+    /// Find every match in `text`, in rule order
+    ///
+    /// `hits` says, for each rule, whether the prefilter saw its pattern.
+    pub(super) fn scan(&self, text: &str, hits: &[bool]) -> Vec<RuleMatch> {
+        let mut matches = vec![];
+        for ((rule, regex), _) in self.rules.iter().zip(hits).filter(|(_, hit)| **hit) {
+            for caps in regex.captures_iter(text) {
+                let Some(m) = redacted_part(&caps).filter(|m| !m.is_empty()) else {
+                    continue;
+                };
+                if rule.validate.is_some_and(|validate| !validate(m.as_str())) {
+                    continue;
+                }
+                matches.push(RuleMatch {
+                    rule: rule.id,
+                    redaction_type: rule.redaction_type.clone(),
+                    start: m.start(),
+                    end: m.end(),
+                    confidence: rule.confidence,
+                });
+            }
+        }
+        matches
+    }
+}
+
+/// Compile a detection regex, reporting a bad pattern as a config error
+pub(super) fn compile(pattern: &str) -> Result<Regex> {
+    Regex::new(pattern)
+        .map_err(|e| GuardError::ConfigError(format!("invalid PII regex {pattern:?}: {e}")))
+}
+
+/// The first capture group that matched, or the whole match
+pub(super) fn redacted_part<'t>(caps: &Captures<'t>) -> Option<Match<'t>> {
+    caps.iter().skip(1).flatten().next().or_else(|| caps.get(0))
+}
+
+/// Every detection regex, compiled into one `RegexSet`
+///
+/// Patterns are registered in groups (built-in patterns, secrets, ...) and
+/// [`Prefilter::scan`] reports hits per group, in registration order.
+pub(super) struct Prefilter {
+    set: RegexSet,
+    groups: Vec<Range<usize>>,
+}
+
+impl Prefilter {
+    /// Compile all groups into one set
+    pub(super) fn new(groups: &[Vec<&str>]) -> Result<Self> {
+        let mut ranges = Vec::with_capacity(groups.len());
+        let mut start = 0;
+        for group in groups {
+            ranges.push(start..start + group.len());
+            start += group.len();
+        }
+        let set = RegexSetBuilder::new(groups.iter().flatten())
+            .size_limit(64 * (1 << 20))
+            .build()
+            .map_err(|e| GuardError::ConfigError(format!("failed to compile PII regexes: {e}")))?;
+        Ok(Self {
+            set,
+            groups: ranges,
+        })
+    }
+
+    /// For each group, which of its patterns occur in `text`
+    pub(super) fn scan(&self, text: &str) -> Vec<Vec<bool>> {
+        let matches = self.set.matches(text);
+        self.groups
+            .iter()
+            .map(|group| group.clone().map(|i| matches.matched(i)).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefilter_groups() {
+        let prefilter =
+            Prefilter::new(&[vec![r"\d{3}", "cat"], vec![], vec!["dog", r"\bfish\b"]]).unwrap();
+        assert_eq!(
+            prefilter.scan("dog 123"),
+            vec![vec![true, false], vec![], vec![true, false]]
+        );
+        assert!(matches!(
+            Prefilter::new(&[vec!["(unclosed"]]),
+            Err(GuardError::ConfigError(_))
+        ));
+    }
+}
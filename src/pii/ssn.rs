@@ -0,0 +1,59 @@
+//! SSN validation and scoring
+//!
+//! Candidates must satisfy the SSA's allocation rules. Dashed numbers are
+//! scored highest; a bare nine-digit run is as likely to be an order ID or a
+//! ZIP+4 code, so it only scores well next to words like "SSN" (see
+//! `context`).
+
+/// Confidence that `value` is an SSN, judged by its format
+///
+/// Returns `None` for numbers the SSA never issues. Context keywords are
+/// applied on top by the detector.
+pub(super) fn confidence(value: &str) -> Option<f32> {
+    let digits: String = value.chars().filter(char::is_ascii_digit).collect();
+    if !valid(&digits) {
+        return None;
+    }
+
+    let separators: Vec<char> = value.chars().filter(|c| !c.is_ascii_digit()).collect();
+    Some(match separators.as_slice() {
+        ['-', '-'] => 0.85,
+        [] => 0.3,
+        _ => 0.6,
+    })
+}
+
+/// SSA rules: no 000, 666 or 9xx area, no 00 group, no 0000 serial
+fn valid(digits: &str) -> bool {
+    if digits.len() != 9 {
+        return false;
+    }
+    let (area, rest) = digits.split_at(3);
+    let (group, serial) = rest.split_at(2);
+    area != "000" && area != "666" && !area.starts_with('9') && group != "00" && serial != "0000"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ssa_rules() {
+        for value in [
+            "000-12-3456",
+            "666-12-3456",
+            "912-12-3456",
+            "123-00-4567",
+            "123-45-0000",
+        ] {
+            assert_eq!(confidence(value), None, "{value}");
+        }
+    }
+
+    #[test]
+    fn test_format_scores() {
+        assert_eq!(confidence("123-45-6789"), Some(0.85));
+        assert_eq!(confidence("123 45 6789"), Some(0.6));
+        assert_eq!(confidence("123456789"), Some(0.3));
+    }
+}
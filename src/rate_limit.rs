@@ -1,34 +1,36 @@
 //! Rate limiting for Guard
 
 use crate::config::RateLimitConfig;
-use crate::error::{GuardError, Result};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+#[cfg(feature = "rate-limit")]
+use crate::error::GuardError;
+use crate::error::Result;
 
+#[cfg(feature = "rate-limit")]
+use governor::middleware::StateInformationMiddleware;
 #[cfg(feature = "rate-limit")]
 use governor::{Quota, RateLimiter as GovernorLimiter};
 #[cfg(feature = "rate-limit")]
+use std::collections::HashMap;
+#[cfg(feature = "rate-limit")]
 use std::num::NonZeroU32;
+#[cfg(feature = "rate-limit")]
+use std::sync::{Arc, RwLock};
+
+/// A single user's token bucket
+#[cfg(feature = "rate-limit")]
+type UserLimiter = GovernorLimiter<
+    governor::state::NotKeyed,
+    governor::state::InMemoryState,
+    governor::clock::DefaultClock,
+    StateInformationMiddleware,
+>;
 
 /// Rate limiter for API requests
 pub struct RateLimiter {
     config: RateLimitConfig,
+    // Never locked across an await, so a reload can read it synchronously
     #[cfg(feature = "rate-limit")]
-    limiters: Arc<
-        RwLock<
-            HashMap<
-                String,
-                Arc<
-                    GovernorLimiter<
-                        governor::state::NotKeyed,
-                        governor::state::InMemoryState,
-                        governor::clock::DefaultClock,
-                    >,
-                >,
-            >,
-        >,
-    >,
+    limiters: Arc<RwLock<HashMap<String, Arc<UserLimiter>>>>,
 }
 
 impl RateLimiter {
@@ -41,6 +43,43 @@ impl RateLimiter {
         }
     }
 
+    /// Create a rate limiter for a reloaded config, keeping how much of
+    /// their quota each user has used in `previous`
+    ///
+    /// With an unchanged quota the buckets are shared as they are. When
+    /// `requests_per_minute` or `burst_size` changes, each user starts with
+    /// no more than the capacity left in their old bucket, so a reload never
+    /// refills anyone's bucket.
+    pub fn with_buckets_from(config: RateLimitConfig, previous: &RateLimiter) -> Self {
+        let same_quota = config.requests_per_minute == previous.config.requests_per_minute
+            && config.burst_size == previous.config.burst_size;
+        if same_quota {
+            return Self {
+                config,
+                #[cfg(feature = "rate-limit")]
+                limiters: previous.limiters.clone(),
+            };
+        }
+
+        #[cfg(feature = "rate-limit")]
+        {
+            let quota = quota(&config);
+            let limiters = previous
+                .limiters
+                .read()
+                .unwrap()
+                .iter()
+                .map(|(user_id, old)| (user_id.clone(), Arc::new(carry_over(quota, old))))
+                .collect();
+            Self {
+                config,
+                limiters: Arc::new(RwLock::new(limiters)),
+            }
+        }
+        #[cfg(not(feature = "rate-limit"))]
+        Self::new(config)
+    }
+
     /// Check if a request is allowed
     #[cfg(feature = "rate-limit")]
     pub async fn check(&self, user_id: &str) -> Result<()> {
@@ -67,41 +106,24 @@ impl RateLimiter {
 
     /// Get or create a limiter for a user
     #[cfg(feature = "rate-limit")]
-    async fn get_or_create_limiter(
-        &self,
-        user_id: &str,
-    ) -> Arc<
-        GovernorLimiter<
-            governor::state::NotKeyed,
-            governor::state::InMemoryState,
-            governor::clock::DefaultClock,
-        >,
-    > {
+    async fn get_or_create_limiter(&self, user_id: &str) -> Arc<UserLimiter> {
         // Try to get existing limiter
         {
-            let limiters = self.limiters.read().await;
+            let limiters = self.limiters.read().unwrap();
             if let Some(limiter) = limiters.get(user_id) {
                 return limiter.clone();
             }
         }
 
         // Create new limiter
-        let mut limiters = self.limiters.write().await;
+        let mut limiters = self.limiters.write().unwrap();
 
         // Double-check after acquiring write lock
         if let Some(limiter) = limiters.get(user_id) {
             return limiter.clone();
         }
 
-        let quota = Quota::per_minute(
-            NonZeroU32::new(self.config.requests_per_minute)
-                .unwrap_or(NonZeroU32::new(60).unwrap()),
-        )
-        .allow_burst(
-            NonZeroU32::new(self.config.burst_size).unwrap_or(NonZeroU32::new(10).unwrap()),
-        );
-
-        let limiter = Arc::new(GovernorLimiter::direct(quota));
+        let limiter = Arc::new(new_limiter(quota(&self.config)));
         limiters.insert(user_id.to_string(), limiter.clone());
 
         limiter
@@ -110,7 +132,7 @@ impl RateLimiter {
     /// Clean up old limiters (call periodically)
     #[cfg(feature = "rate-limit")]
     pub async fn cleanup(&self) {
-        let mut limiters = self.limiters.write().await;
+        let mut limiters = self.limiters.write().unwrap();
         // In a production system, you'd track last access time
         // and remove limiters that haven't been used recently
         if limiters.len() > 10000 {
@@ -156,6 +178,38 @@ impl RateLimiter {
     }
 }
 
+/// Quota for a rate limit config
+#[cfg(feature = "rate-limit")]
+fn quota(config: &RateLimitConfig) -> Quota {
+    Quota::per_minute(
+        NonZeroU32::new(config.requests_per_minute).unwrap_or(NonZeroU32::new(60).unwrap()),
+    )
+    .allow_burst(NonZeroU32::new(config.burst_size).unwrap_or(NonZeroU32::new(10).unwrap()))
+}
+
+#[cfg(feature = "rate-limit")]
+fn new_limiter(quota: Quota) -> UserLimiter {
+    GovernorLimiter::direct(quota).with_middleware()
+}
+
+/// A bucket for `quota` with at most the capacity left in `old`
+///
+/// Governor only reports the remaining capacity alongside an allowed cell,
+/// so reading it takes one cell from the old bucket, which is being retired.
+#[cfg(feature = "rate-limit")]
+fn carry_over(quota: Quota, old: &UserLimiter) -> UserLimiter {
+    let remaining = old
+        .check()
+        .map_or(0, |state| state.remaining_burst_capacity() + 1);
+    let limiter = new_limiter(quota);
+    let used = quota.burst_size().get().saturating_sub(remaining);
+    if let Some(used) = NonZeroU32::new(used) {
+        // Cannot fail: `used` is below the burst size of a full bucket
+        let _ = limiter.check_n(used);
+    }
+    limiter
+}
+
 /// Rate limit status
 #[derive(Debug, Clone)]
 pub struct RateLimitStatus {
@@ -184,6 +238,68 @@ mod tests {
         assert!(limiter.check("user1").await.is_ok());
     }
 
+    #[tokio::test]
+    #[cfg(feature = "rate-limit")]
+    async fn test_buckets_survive_reload() {
+        let config = RateLimitConfig {
+            enabled: true,
+            requests_per_minute: 1,
+            burst_size: 1,
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config.clone());
+        assert!(limiter.check("user1").await.is_ok());
+
+        // Same quota: the exhausted bucket carries over
+        let reloaded = RateLimiter::with_buckets_from(config.clone(), &limiter);
+        assert!(reloaded.check("user1").await.is_err());
+
+        // Other users still start with a full bucket
+        assert!(reloaded.check("user2").await.is_ok());
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "rate-limit")]
+    async fn test_buckets_survive_quota_change() {
+        let config = RateLimitConfig {
+            enabled: true,
+            requests_per_minute: 1,
+            burst_size: 3,
+            ..Default::default()
+        };
+        let limiter = RateLimiter::new(config.clone());
+        assert!(limiter.check("user1").await.is_ok());
+        assert!(limiter.check("user1").await.is_ok());
+        for _ in 0..3 {
+            assert!(limiter.check("user2").await.is_ok());
+        }
+
+        // A larger burst does not refill the buckets
+        let resized = RateLimiter::with_buckets_from(
+            RateLimitConfig {
+                burst_size: 5,
+                ..config.clone()
+            },
+            &limiter,
+        );
+        assert!(resized.check("user1").await.is_ok());
+        assert!(resized.check("user1").await.is_err());
+        assert!(resized.check("user2").await.is_err());
+
+        // A smaller burst caps what is left
+        let limiter = RateLimiter::new(config.clone());
+        assert!(limiter.check("user1").await.is_ok());
+        let shrunk = RateLimiter::with_buckets_from(
+            RateLimitConfig {
+                burst_size: 1,
+                ..config
+            },
+            &limiter,
+        );
+        assert!(shrunk.check("user1").await.is_ok());
+        assert!(shrunk.check("user1").await.is_err());
+    }
+
     #[tokio::test]
     #[cfg(feature = "rate-limit")]
     async fn test_rate_limit_basic() {
@@ -0,0 +1,464 @@
+//! Pluggable pipeline stages
+//!
+//! [`Guard`](crate::Guard) runs content through an ordered list of stages.
+//! The built-in stages wrap the rate limiter, injection detector, PII
+//! detector and content filter; custom stages implement [`Stage`] and are
+//! added with [`GuardBuilder::with_stage`](crate::guard::GuardBuilder::with_stage).
+
+use crate::config::{RedactionStrategy, StageMode};
+use crate::content::ContentFilter;
+use crate::error::{GuardError, Result, SafetyCategory};
+use crate::injection::InjectionDetector;
+use crate::pii::PiiDetector;
+use crate::rate_limit::RateLimiter;
+use crate::types::{Direction, GuardContext, Redaction};
+use crate::vault::TokenVault;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A single step of the sanitization pipeline
+#[async_trait]
+pub trait Stage: Send + Sync {
+    /// Unique stage name, used to reorder, replace or disable stages
+    fn name(&self) -> &str;
+
+    /// Whether the stage's decisions are enforced or only recorded
+    fn mode(&self) -> StageMode {
+        StageMode::Enforce
+    }
+
+    /// Whether the stage runs for content flowing in this direction
+    fn applies_to(&self, direction: Direction) -> bool {
+        let _ = direction;
+        true
+    }
+
+    /// Inspect the text as left by the previous stages
+    async fn inspect(
+        &self,
+        text: &str,
+        direction: Direction,
+        context: &GuardContext,
+    ) -> Result<StageOutcome>;
+}
+
+/// What a stage wants done with the content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum StageAction {
+    /// Pass the text through unchanged
+    Allow,
+    /// Replace spans of the text
+    ///
+    /// Spans refer to the text the stage was given and must be sorted by
+    /// start position without overlapping.
+    Redact(Vec<Redaction>),
+    /// Block the content entirely
+    Block {
+        /// Reason for blocking
+        reason: String,
+        /// Safety category that triggered the block
+        category: SafetyCategory,
+    },
+}
+
+/// Something a stage noticed, whether or not it acted on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    /// What was found (e.g. "Email", "PromptOverride")
+    pub kind: String,
+    /// Additional detail, such as the matched pattern
+    pub detail: Option<String>,
+    /// Byte span in the text the stage inspected
+    pub span: Option<(usize, usize)>,
+}
+
+impl Finding {
+    /// Create a finding of the given kind
+    pub fn new(kind: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            detail: None,
+            span: None,
+        }
+    }
+
+    /// Attach detail
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Attach a byte span
+    pub fn with_span(mut self, start: usize, end: usize) -> Self {
+        self.span = Some((start, end));
+        self
+    }
+}
+
+/// Result of running a stage
+#[derive(Debug, Clone)]
+pub struct StageOutcome {
+    /// Action to take
+    pub action: StageAction,
+    /// Findings that led to the action
+    pub findings: Vec<Finding>,
+    /// Stage-specific score (e.g. injection confidence)
+    pub score: Option<f32>,
+}
+
+impl StageOutcome {
+    /// Let the content through
+    pub fn allow() -> Self {
+        Self {
+            action: StageAction::Allow,
+            findings: vec![],
+            score: None,
+        }
+    }
+
+    /// Redact the given spans (allows if there are none)
+    pub fn redact(redactions: Vec<Redaction>) -> Self {
+        let action = if redactions.is_empty() {
+            StageAction::Allow
+        } else {
+            StageAction::Redact(redactions)
+        };
+        Self {
+            action,
+            findings: vec![],
+            score: None,
+        }
+    }
+
+    /// Block the content
+    pub fn block(reason: impl Into<String>, category: SafetyCategory) -> Self {
+        Self {
+            action: StageAction::Block {
+                reason: reason.into(),
+                category,
+            },
+            findings: vec![],
+            score: None,
+        }
+    }
+
+    /// Attach findings
+    pub fn with_findings(mut self, findings: Vec<Finding>) -> Self {
+        self.findings = findings;
+        self
+    }
+
+    /// Attach a score
+    pub fn with_score(mut self, score: f32) -> Self {
+        self.score = Some(score);
+        self
+    }
+}
+
+/// Per-user rate limiting (input only)
+pub struct RateLimitStage {
+    limiter: Arc<RateLimiter>,
+    mode: StageMode,
+}
+
+impl RateLimitStage {
+    /// Stage name
+    pub const NAME: &'static str = "rate_limit";
+
+    /// Wrap a rate limiter
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self {
+            limiter,
+            mode: StageMode::Enforce,
+        }
+    }
+
+    /// Set the stage mode
+    pub fn with_mode(mut self, mode: StageMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+#[async_trait]
+impl Stage for RateLimitStage {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn mode(&self) -> StageMode {
+        self.mode
+    }
+
+    fn applies_to(&self, direction: Direction) -> bool {
+        direction == Direction::Input
+    }
+
+    async fn inspect(
+        &self,
+        _text: &str,
+        _direction: Direction,
+        context: &GuardContext,
+    ) -> Result<StageOutcome> {
+        let user_id = context.user_id.as_deref().unwrap_or("anonymous");
+        match self.limiter.check(user_id).await {
+            Ok(()) => Ok(StageOutcome::allow()),
+            // Surface the would-be rejection as a block so it can be recorded
+            Err(GuardError::RateLimitExceeded(reason)) if self.mode == StageMode::Monitor => {
+                Ok(StageOutcome::block(reason, SafetyCategory::None))
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Prompt injection detection (input only)
+pub struct InjectionStage {
+    detector: InjectionDetector,
+    mode: StageMode,
+}
+
+impl InjectionStage {
+    /// Stage name
+    pub const NAME: &'static str = "injection";
+
+    /// Wrap an injection detector
+    pub fn new(detector: InjectionDetector) -> Self {
+        Self {
+            detector,
+            mode: StageMode::Enforce,
+        }
+    }
+
+    /// Set the stage mode
+    pub fn with_mode(mut self, mode: StageMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+#[async_trait]
+impl Stage for InjectionStage {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn mode(&self) -> StageMode {
+        self.mode
+    }
+
+    fn applies_to(&self, direction: Direction) -> bool {
+        direction == Direction::Input
+    }
+
+    async fn inspect(
+        &self,
+        text: &str,
+        _direction: Direction,
+        _context: &GuardContext,
+    ) -> Result<StageOutcome> {
+        let result = self.detector.detect(text);
+        let findings = result
+            .patterns
+            .iter()
+            .map(|p| {
+                Finding::new(format!("{:?}", p.injection_type))
+                    .with_detail(&p.pattern)
+                    .with_span(p.position, p.position + p.pattern.len())
+            })
+            .collect();
+
+        let outcome = if self.detector.should_block(&result) {
+            StageOutcome::block(
+                format!(
+                    "Prompt injection detected (confidence: {:.2})",
+                    result.confidence
+                ),
+                InjectionDetector::safety_category(),
+            )
+        } else {
+            StageOutcome::allow()
+        };
+
+        Ok(outcome
+            .with_findings(findings)
+            .with_score(result.confidence))
+    }
+}
+
+/// PII detection and redaction
+pub struct PiiStage {
+    detector: PiiDetector,
+    vault: Option<Arc<dyn TokenVault>>,
+    mode: StageMode,
+}
+
+impl PiiStage {
+    /// Stage name
+    pub const NAME: &'static str = "pii";
+
+    /// Wrap a PII detector
+    pub fn new(detector: PiiDetector) -> Self {
+        Self {
+            detector,
+            vault: None,
+            mode: StageMode::Enforce,
+        }
+    }
+
+    /// Store tokenized values in this vault
+    ///
+    /// Without a vault, and for PII found in model output, types using
+    /// [`RedactionStrategy::Token`] fall back to the placeholder format.
+    pub fn with_vault(mut self, vault: Arc<dyn TokenVault>) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    /// Set the stage mode
+    pub fn with_mode(mut self, mode: StageMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+#[async_trait]
+impl Stage for PiiStage {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn mode(&self) -> StageMode {
+        self.mode
+    }
+
+    async fn inspect(
+        &self,
+        text: &str,
+        direction: Direction,
+        context: &GuardContext,
+    ) -> Result<StageOutcome> {
+        let request = context.request_id.to_string();
+        let session = context.session_id.as_deref().unwrap_or(&request);
+
+        let detected = self.detector.detect_with_context(text, context);
+        if let Some(r) = detected.iter().find(|r| self.detector.blocks(r)) {
+            let finding = Finding::new(r.redaction_type.to_string()).with_span(r.start, r.end);
+            return Ok(StageOutcome::block(
+                format!("Matched a term from the {} dictionary", r.redaction_type),
+                SafetyCategory::Pii,
+            )
+            .with_findings(vec![finding]));
+        }
+
+        // In monitor mode nothing is replaced, so report the placeholders
+        // rather than minting tokens or fakes
+        let monitor = self.mode == StageMode::Monitor;
+        let mut redactions = Vec::new();
+        for mut r in detected {
+            let value = &text[r.start..r.end];
+            match self.detector.strategy(&r.redaction_type) {
+                _ if monitor => {}
+                // Tokens are only minted on input; a token for PII the model
+                // leaked would be restored along with the input's
+                RedactionStrategy::Token if direction == Direction::Input => {
+                    if let Some(vault) = &self.vault {
+                        r.replacement = vault.tokenize(&request, &r.redaction_type, value).await?;
+                    }
+                }
+                RedactionStrategy::Pseudonym => {
+                    match self
+                        .detector
+                        .pseudonymize(session, &r.redaction_type, value)
+                    {
+                        Some(fake) => r.replacement = fake,
+                        // Already a fake, leave it alone
+                        None => continue,
+                    }
+                }
+                // Applied by the detector
+                _ => {}
+            }
+            redactions.push(r);
+        }
+
+        let findings = redactions
+            .iter()
+            .map(|r| {
+                let finding = Finding::new(r.redaction_type.to_string()).with_span(r.start, r.end);
+                match &r.rule {
+                    Some(rule) => finding.with_detail(rule),
+                    None => finding,
+                }
+            })
+            .collect();
+
+        Ok(StageOutcome::redact(redactions).with_findings(findings))
+    }
+}
+
+/// Content safety classification via Zen Guard
+pub struct ContentFilterStage {
+    filter: ContentFilter,
+    enabled: bool,
+    mode: StageMode,
+}
+
+impl ContentFilterStage {
+    /// Stage name
+    pub const NAME: &'static str = "content_filter";
+
+    /// Wrap a content filter; a disabled filter allows everything without a request
+    pub fn new(filter: ContentFilter, enabled: bool) -> Self {
+        Self {
+            filter,
+            enabled,
+            mode: StageMode::Enforce,
+        }
+    }
+
+    /// Set the stage mode
+    pub fn with_mode(mut self, mode: StageMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+#[async_trait]
+impl Stage for ContentFilterStage {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn mode(&self) -> StageMode {
+        self.mode
+    }
+
+    async fn inspect(
+        &self,
+        text: &str,
+        direction: Direction,
+        _context: &GuardContext,
+    ) -> Result<StageOutcome> {
+        if !self.enabled {
+            return Ok(StageOutcome::allow());
+        }
+
+        let result = self
+            .filter
+            .check(text, direction == Direction::Output)
+            .await?;
+        let findings = result
+            .categories
+            .iter()
+            .map(|c| Finding::new(format!("{:?}", result.safety_level)).with_detail(c.to_string()))
+            .collect();
+
+        let outcome = match self.filter.should_block(&result) {
+            Some((reason, category)) => StageOutcome::block(reason, category),
+            None => StageOutcome::allow(),
+        };
+        Ok(outcome.with_findings(findings))
+    }
+}
@@ -1,6 +1,8 @@
 //! Core types for Hanzo Guard
 
+use crate::config::StageMode;
 use crate::error::SafetyCategory;
+use crate::stage::{Finding, StageAction};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -49,6 +51,64 @@ impl SanitizeResult {
     }
 }
 
+/// An action a monitor-mode stage would have taken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitoredAction {
+    /// Name of the stage
+    pub stage: String,
+    /// The redaction or block that was not enforced
+    pub action: StageAction,
+}
+
+/// Full account of how the pipeline handled one piece of content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardReport {
+    /// Request ID from the context
+    pub request_id: Uuid,
+    /// Direction of the content
+    pub direction: Direction,
+    /// Final decision, made by the enforcing stages
+    pub decision: SanitizeResult,
+    /// What stages in monitor mode would have done
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub monitored: Vec<MonitoredAction>,
+    /// Every stage that ran, in order
+    pub stages: Vec<StageReport>,
+    /// Total processing time in microseconds
+    pub total_time_us: u64,
+}
+
+impl GuardReport {
+    /// The enforcing stage that blocked the content, if any
+    pub fn blocked_by(&self) -> Option<&StageReport> {
+        self.stages
+            .iter()
+            .find(|s| s.mode == StageMode::Enforce && matches!(s.action, StageAction::Block { .. }))
+    }
+
+    /// Report for a stage by name
+    pub fn stage(&self, name: &str) -> Option<&StageReport> {
+        self.stages.iter().find(|s| s.stage == name)
+    }
+}
+
+/// What a single stage found and decided
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageReport {
+    /// Name of the stage
+    pub stage: String,
+    /// Mode the stage ran in
+    pub mode: StageMode,
+    /// Action the stage asked for (not applied in monitor mode)
+    pub action: StageAction,
+    /// Findings reported by the stage
+    pub findings: Vec<Finding>,
+    /// Stage-specific score, e.g. injection confidence
+    pub score: Option<f32>,
+    /// Time spent in the stage in microseconds
+    pub duration_us: u64,
+}
+
 /// A redaction made to content
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Redaction {
@@ -62,10 +122,23 @@ pub struct Redaction {
     pub start: usize,
     /// End position in original text
     pub end: usize,
+    /// Detection rule, e.g. `github_token` for an API key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Confidence that the match is what `redaction_type` says (0.0 - 1.0)
+    #[serde(default = "full_confidence")]
+    pub confidence: f32,
+    /// Nearby keywords that raised or lowered the confidence
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<String>,
+}
+
+fn full_confidence() -> f32 {
+    1.0
 }
 
 /// Types of redactions
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RedactionType {
     /// Social Security Number
     Ssn,
@@ -81,8 +154,40 @@ pub enum RedactionType {
     ApiKey,
     /// Password
     Password,
+    /// International Bank Account Number
+    Iban,
+    /// UK National Insurance number
+    UkNino,
+    /// Canadian Social Insurance Number
+    CaSin,
+    /// Indian Aadhaar number
+    InAadhaar,
+    /// Indian Permanent Account Number
+    InPan,
+    /// European national identity number (see the redaction's rule)
+    EuNationalId,
+    /// Person's name
+    PersonName,
+    /// Street address
+    Address,
+    /// Date of birth
+    DateOfBirth,
+    /// Signature of a pre-signed or shared URL
+    Url,
+    /// Credential embedded in a URL: userinfo or a token-like parameter
+    Credential,
+    /// Bitcoin or Ethereum wallet address
+    CryptoWallet,
+    /// Network interface MAC address
+    MacAddress,
+    /// Mobile device IMEI
+    Imei,
+    /// UUID-form device or advertising identifier
+    DeviceId,
     /// Other PII
     OtherPii,
+    /// Term from a customer-defined dictionary, labelled with the dictionary's name
+    Custom(String),
 }
 
 impl std::fmt::Display for RedactionType {
@@ -95,7 +200,55 @@ impl std::fmt::Display for RedactionType {
             RedactionType::IpAddress => write!(f, "IP Address"),
             RedactionType::ApiKey => write!(f, "API Key"),
             RedactionType::Password => write!(f, "Password"),
+            RedactionType::Iban => write!(f, "IBAN"),
+            RedactionType::UkNino => write!(f, "NI Number"),
+            RedactionType::CaSin => write!(f, "SIN"),
+            RedactionType::InAadhaar => write!(f, "Aadhaar"),
+            RedactionType::InPan => write!(f, "PAN"),
+            RedactionType::EuNationalId => write!(f, "National ID"),
+            RedactionType::PersonName => write!(f, "Name"),
+            RedactionType::Address => write!(f, "Address"),
+            RedactionType::DateOfBirth => write!(f, "Date of Birth"),
+            RedactionType::Url => write!(f, "URL"),
+            RedactionType::Credential => write!(f, "Credential"),
+            RedactionType::CryptoWallet => write!(f, "Crypto Wallet"),
+            RedactionType::MacAddress => write!(f, "MAC Address"),
+            RedactionType::Imei => write!(f, "IMEI"),
+            RedactionType::DeviceId => write!(f, "Device ID"),
             RedactionType::OtherPii => write!(f, "Other PII"),
+            RedactionType::Custom(label) => write!(f, "{label}"),
+        }
+    }
+}
+
+impl RedactionType {
+    /// Label used in reversible tokens, e.g. `EMAIL` in `<EMAIL_1>`
+    pub fn token_label(&self) -> &str {
+        match self {
+            RedactionType::Ssn => "SSN",
+            RedactionType::CreditCard => "CREDIT_CARD",
+            RedactionType::Email => "EMAIL",
+            RedactionType::Phone => "PHONE",
+            RedactionType::IpAddress => "IP_ADDRESS",
+            RedactionType::ApiKey => "API_KEY",
+            RedactionType::Password => "PASSWORD",
+            RedactionType::Iban => "IBAN",
+            RedactionType::UkNino => "UK_NINO",
+            RedactionType::CaSin => "CA_SIN",
+            RedactionType::InAadhaar => "AADHAAR",
+            RedactionType::InPan => "IN_PAN",
+            RedactionType::EuNationalId => "NATIONAL_ID",
+            RedactionType::PersonName => "PERSON",
+            RedactionType::Address => "ADDRESS",
+            RedactionType::DateOfBirth => "DOB",
+            RedactionType::Url => "URL",
+            RedactionType::Credential => "CREDENTIAL",
+            RedactionType::CryptoWallet => "CRYPTO_WALLET",
+            RedactionType::MacAddress => "MAC_ADDRESS",
+            RedactionType::Imei => "IMEI",
+            RedactionType::DeviceId => "DEVICE_ID",
+            RedactionType::OtherPii => "PII",
+            RedactionType::Custom(label) => label,
         }
     }
 }
@@ -185,6 +338,9 @@ pub struct AuditEntry {
     pub result: AuditResult,
     /// Processing time in milliseconds
     pub processing_time_ms: u64,
+    /// Actions recorded by stages in monitor mode
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub monitored: Vec<MonitoredAction>,
 }
 
 /// Direction of content flow
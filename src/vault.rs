@@ -0,0 +1,183 @@
+//! Token vault for reversible PII redaction
+//!
+//! With [`RedactionStrategy::Token`](crate::config::RedactionStrategy::Token),
+//! detected values are replaced by placeholders such as `<EMAIL_1>` and the
+//! originals are kept in a vault, scoped to the request. Output sanitized with
+//! the same [`GuardContext`](crate::types::GuardContext) gets the originals
+//! restored before it reaches the caller.
+
+use crate::error::Result;
+use crate::types::RedactionType;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Storage for token ↔ original value mappings
+///
+/// Scopes isolate requests from each other; Guard uses the request ID of
+/// the [`GuardContext`](crate::types::GuardContext) as the scope.
+#[async_trait]
+pub trait TokenVault: Send + Sync {
+    /// Return the token for `value` in `scope`, minting a new one if needed
+    ///
+    /// The same value must map to the same token within a scope.
+    async fn tokenize(
+        &self,
+        scope: &str,
+        redaction_type: &RedactionType,
+        value: &str,
+    ) -> Result<String>;
+
+    /// All `(token, original)` pairs stored for `scope`
+    async fn mappings(&self, scope: &str) -> Result<Vec<(String, String)>>;
+
+    /// Forget everything stored for `scope`
+    async fn release(&self, scope: &str) -> Result<()>;
+
+    /// Replace every token from `scope` in `text` with its original value
+    async fn restore(&self, scope: &str, text: &str) -> Result<String> {
+        Ok(restore_tokens(text, &self.mappings(scope).await?))
+    }
+}
+
+/// Tokens minted for a single scope
+#[derive(Default)]
+struct VaultScope {
+    tokens: HashMap<(RedactionType, String), String>,
+    originals: HashMap<String, String>,
+    counters: HashMap<RedactionType, usize>,
+}
+
+/// Process-local token vault
+#[derive(Default)]
+pub struct InMemoryTokenVault {
+    scopes: RwLock<HashMap<String, VaultScope>>,
+}
+
+impl InMemoryTokenVault {
+    /// Create an empty vault
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of scopes currently held
+    pub async fn scope_count(&self) -> usize {
+        self.scopes.read().await.len()
+    }
+}
+
+#[async_trait]
+impl TokenVault for InMemoryTokenVault {
+    async fn tokenize(
+        &self,
+        scope: &str,
+        redaction_type: &RedactionType,
+        value: &str,
+    ) -> Result<String> {
+        let mut scopes = self.scopes.write().await;
+        let entry = scopes.entry(scope.to_string()).or_default();
+
+        let key = (redaction_type.clone(), value.to_string());
+        if let Some(token) = entry.tokens.get(&key) {
+            return Ok(token.clone());
+        }
+
+        let counter = entry.counters.entry(redaction_type.clone()).or_insert(0);
+        *counter += 1;
+        let token = format!("<{}_{}>", redaction_type.token_label(), counter);
+
+        entry.originals.insert(token.clone(), value.to_string());
+        entry.tokens.insert(key, token.clone());
+        Ok(token)
+    }
+
+    async fn mappings(&self, scope: &str) -> Result<Vec<(String, String)>> {
+        let scopes = self.scopes.read().await;
+        Ok(scopes
+            .get(scope)
+            .map(|s| {
+                s.originals
+                    .iter()
+                    .map(|(token, original)| (token.clone(), original.clone()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn release(&self, scope: &str) -> Result<()> {
+        self.scopes.write().await.remove(scope);
+        Ok(())
+    }
+}
+
+/// Replace each token in `text` with its original value
+///
+/// Tokens are delimited by `<` and `>`, so `<EMAIL_1>` never matches inside
+/// `<EMAIL_10>`.
+pub fn restore_tokens(text: &str, mappings: &[(String, String)]) -> String {
+    mappings
+        .iter()
+        .fold(text.to_string(), |text, (token, original)| {
+            if text.contains(token.as_str()) {
+                text.replace(token.as_str(), original)
+            } else {
+                text
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tokens_are_stable_per_scope() {
+        let vault = InMemoryTokenVault::new();
+
+        let a = vault
+            .tokenize("req1", &RedactionType::Email, "a@example.com")
+            .await
+            .unwrap();
+        let b = vault
+            .tokenize("req1", &RedactionType::Email, "b@example.com")
+            .await
+            .unwrap();
+        let again = vault
+            .tokenize("req1", &RedactionType::Email, "a@example.com")
+            .await
+            .unwrap();
+        let other_scope = vault
+            .tokenize("req2", &RedactionType::Email, "b@example.com")
+            .await
+            .unwrap();
+
+        assert_eq!(a, "<EMAIL_1>");
+        assert_eq!(b, "<EMAIL_2>");
+        assert_eq!(again, a);
+        assert_eq!(other_scope, "<EMAIL_1>");
+    }
+
+    #[tokio::test]
+    async fn test_restore_and_release() {
+        let vault = InMemoryTokenVault::new();
+        for i in 1..=10 {
+            vault
+                .tokenize("req", &RedactionType::Phone, &format!("555-000{i}"))
+                .await
+                .unwrap();
+        }
+
+        let restored = vault
+            .restore("req", "Call <PHONE_1> or <PHONE_10>")
+            .await
+            .unwrap();
+        assert_eq!(restored, "Call 555-0001 or 555-00010");
+
+        vault.release("req").await.unwrap();
+        assert_eq!(vault.scope_count().await, 0);
+        assert_eq!(
+            vault.restore("req", "Call <PHONE_1>").await.unwrap(),
+            "Call <PHONE_1>"
+        );
+    }
+}